@@ -0,0 +1,144 @@
+//! Clocks of the network. Time in the network is synchronized between all systems but is
+//! known only with some error bound. Local monotonic clock is precise and never goes back
+//! but is meaningful only on the local system. Clock readings are provided by the
+//! [ClockBackend] of the network which can be replaced, for example, to run the code on
+//! the virtual time of a simulator.
+
+use core::time::Duration;
+use core::convert::TryFrom;
+use alloc::boxed::Box;
+use time::Time;
+use crate::path::Network;
+use crate::{kobzar_env, KobzarEnv};
+
+/// Time in the network with its error bound. Real time is within `time ± error_bound`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct NetworkTime {
+    pub time: Time,
+    pub error_bound: Duration,
+}
+
+/// Reading of the local monotonic clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant(Duration);
+
+impl Instant {
+    /// Instant that is given duration after the start of the local system.
+    pub fn from_start(since_start: Duration) -> Self {
+        Instant(since_start)
+    }
+
+    /// Time elapsed from the start of the local system to this instant.
+    pub fn since_start(&self) -> Duration {
+        self.0
+    }
+
+    /// Time elapsed from the earlier instant to this one. Zero is returned if earlier instant
+    /// is actually later than this one.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        self.0.checked_sub(earlier.0).unwrap_or_default()
+    }
+
+    /// Time elapsed since this instant.
+    pub fn elapsed(&self) -> Duration {
+        monotonic().duration_since(*self)
+    }
+
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        self.0.checked_add(duration).map(Instant)
+    }
+
+    pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+        self.0.checked_sub(duration).map(Instant)
+    }
+}
+
+/// Source of the clock readings.
+pub trait ClockBackend {
+    /// Current time in the network.
+    fn network_time(&self) -> NetworkTime;
+
+    /// Time elapsed since the start of the local system.
+    fn monotonic(&self) -> Duration;
+}
+
+/// Current time in the network.
+pub fn network_time() -> NetworkTime {
+    kobzar_env().network().clock().network_time()
+}
+
+/// Current reading of the local monotonic clock.
+pub fn monotonic() -> Instant {
+    Instant(kobzar_env().network().clock().monotonic())
+}
+
+/// Convert local instant to the time in the network. Error bound of the result is the one of
+/// the current network time.
+pub fn to_network(instant: Instant) -> NetworkTime {
+    let clock = kobzar_env().network().clock();
+    let now = clock.network_time();
+    let mono = clock.monotonic();
+    let time = if instant.0 >= mono {
+        now.time + (instant.0 - mono)
+    } else {
+        now.time - (mono - instant.0)
+    };
+    NetworkTime {
+        time,
+        error_bound: now.error_bound,
+    }
+}
+
+/// Convert time in the network to the local instant. Time of day is assumed to be the
+/// nearest to the current one, that is, within 12 hours before or after it. None is returned
+/// if time is before the start of the local system.
+pub fn to_monotonic(time: Time) -> Option<Instant> {
+    let clock = kobzar_env().network().clock();
+    nearest_instant(clock.network_time().time, Instant(clock.monotonic()), time)
+}
+
+/// Instant of the time given that `now` is at `mono` instant. Difference between the times
+/// is wrapped around midnight into the range (-12h, 12h].
+fn nearest_instant(now: Time, mono: Instant, time: Time) -> Option<Instant> {
+    let half_day = time::Duration::hours(12);
+    let mut diff = time - now;
+    if diff > half_day {
+        diff -= time::Duration::day();
+    } else if diff <= -half_day {
+        diff += time::Duration::day();
+    }
+    let abs = Duration::try_from(diff.abs()).ok()?;
+    if diff.is_negative() {
+        mono.checked_sub(abs)
+    } else {
+        mono.checked_add(abs)
+    }
+}
+
+/// Replace clock backend of the network. This affects all threads of the local system.
+pub fn set_backend(backend: Box<dyn ClockBackend>) {
+    kobzar_env().network_mut().set_clock_backend(backend)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u8, m: u8) -> Time {
+        Time::try_from_hms(h, m, 0).ok().unwrap()
+    }
+
+    fn at(secs: u64) -> Instant {
+        Instant::from_start(Duration::from_secs(secs))
+    }
+
+    #[test]
+    fn nearest_across_midnight() {
+        let mono = at(3600);
+        assert_eq!(nearest_instant(time(23, 59), mono, time(0, 1)), Some(at(3600 + 120)));
+        assert_eq!(nearest_instant(time(0, 1), mono, time(23, 59)), Some(at(3600 - 120)));
+        assert_eq!(nearest_instant(time(10, 0), mono, time(10, 30)), Some(at(3600 + 1800)));
+        assert_eq!(nearest_instant(time(12, 0), mono, time(0, 0)), Some(at(3600 + 12 * 3600)));
+        assert_eq!(nearest_instant(time(0, 0), mono, time(22, 0)), None);
+    }
+}
@@ -8,11 +8,29 @@ use crate::path::Network;
 
 pub mod path;
 
+pub mod pattern;
+
+pub mod version;
+
 /// KobzarEnv resources mapped to app's memory.
 mod rsc;
 
 pub mod msg;
 
+pub mod notif;
+
+pub mod supervisor;
+
+pub mod watchdog;
+
+pub mod timer;
+
+pub mod watch;
+
+pub mod registry;
+
+pub mod clock;
+
 /// Unimplemented environment. Is used while developing library to check for compilation
 /// errors in implementer-agnostic code.
 mod unimpled;
@@ -0,0 +1,189 @@
+//! System notifications. Network delivers them to the mailbox of the thread like any other
+//! message so they can be received with [Input::get] or waited for with
+//! [wait_any](crate::msg::wait_any) together with other interfaces.
+
+use alloc::rc::Rc;
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+use crate::path::{Interface, Network, OwnedPath, Version};
+use crate::thread::State;
+use crate::msg::Input;
+use crate::{kobzar_env, KobzarEnv, Uid};
+use crate::watchdog::Action;
+use core::time::Duration;
+use time::Time;
+
+/// Interfaces that are used by the network to deliver system notifications.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SystemInterface {
+    PowerSave,
+    EstimateOverrun,
+    CacheExpiry,
+    HeartbeatMissed,
+    TimerFired,
+    InstanceEvent,
+}
+
+fn decode<T: DeserializeOwned>(b: &[u8]) -> T {
+    postcard::from_bytes(b).expect("network delivered malformed system notification")
+}
+
+/// Power-saving mode change. Delivered to threads that
+/// [subscribed](crate::thread::OwnedThread::set_powersave_subscription) to it.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PowerSaveNotification {
+    /// System entered power-saving mode.
+    Entered,
+
+    /// System left power-saving mode.
+    Left,
+}
+
+impl Input for PowerSaveNotification {
+    fn interface() -> &'static Rc<Interface> {
+        kobzar_env().network().system_interface(SystemInterface::PowerSave)
+    }
+
+    fn from_msg_bytes(b: &[u8]) -> Self {
+        decode(b)
+    }
+}
+
+/// Task has exhausted its `estimate_left` and `margin`. Delivered to the task and, if
+/// [requested](crate::thread::ThreadBuilder::notify_owner_on_overrun), to its owner.
+/// Task should [respond](crate::thread::OwnedThread::respond_to_overrun) to let the
+/// scheduler know how to proceed.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EstimateOverrun {
+    /// UID of the task thread.
+    pub task: Uid,
+
+    /// CPU time the task has consumed over its estimate and margin.
+    pub overrun: Duration,
+}
+
+impl Input for EstimateOverrun {
+    fn interface() -> &'static Rc<Interface> {
+        kobzar_env().network().system_interface(SystemInterface::EstimateOverrun)
+    }
+
+    fn from_msg_bytes(b: &[u8]) -> Self {
+        decode(b)
+    }
+}
+
+/// Scheduler failed to run the caching task before its `until` time. Task should
+/// [respond](crate::thread::OwnedThread::respond_to_expiry) with the new time, new type or
+/// cease. If it does not, its [ExpiryPolicy](crate::thread::ExpiryPolicy) is applied.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheExpiry {
+    /// UID of the task thread.
+    pub task: Uid,
+
+    /// Time in the network until which the data should have been cached.
+    pub until: Time,
+}
+
+impl Input for CacheExpiry {
+    fn interface() -> &'static Rc<Interface> {
+        kobzar_env().network().system_interface(SystemInterface::CacheExpiry)
+    }
+
+    fn from_msg_bytes(b: &[u8]) -> Self {
+        decode(b)
+    }
+}
+
+/// Thread with a watchdog missed its heartbeat. Delivered to the owner of the thread.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeartbeatMissed {
+    /// UID of the thread that missed the heartbeat.
+    pub thread: Uid,
+
+    /// Time elapsed since the last heartbeat.
+    pub since_last: Duration,
+
+    /// Action that was taken by the network.
+    pub action: Action,
+}
+
+impl Input for HeartbeatMissed {
+    fn interface() -> &'static Rc<Interface> {
+        kobzar_env().network().system_interface(SystemInterface::HeartbeatMissed)
+    }
+
+    fn from_msg_bytes(b: &[u8]) -> Self {
+        decode(b)
+    }
+}
+
+/// [Timer](crate::timer::Timer) of the thread has fired.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimerFired {
+    /// UID of the timer.
+    pub timer: Uid,
+
+    /// Tag given when the timer was started.
+    pub tag: u64,
+
+    /// Amount of periods of the periodic timer that elapsed since the previous message
+    /// was sent but were not delivered because the message was still pending.
+    pub missed: u32,
+}
+
+impl Input for TimerFired {
+    fn interface() -> &'static Rc<Interface> {
+        kobzar_env().network().system_interface(SystemInterface::TimerFired)
+    }
+
+    fn from_msg_bytes(b: &[u8]) -> Self {
+        decode(b)
+    }
+}
+
+/// Kind of change of the watched instance.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstanceEventKind {
+    /// Instance started to satisfy the request of the watch.
+    Added,
+
+    /// Instance no longer satisfies the request or its thread has died.
+    Removed,
+
+    /// State of the thread that implements the instance has changed but instance still
+    /// satisfies the request.
+    StateChanged,
+}
+
+/// Instance matching the request of the [Watch](crate::watch::Watch) has changed.
+/// Delivered to the thread that started the watch.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InstanceEvent {
+    /// UID of the watch.
+    pub watch: Uid,
+
+    /// What has happened to the instance.
+    pub kind: InstanceEventKind,
+
+    /// UID of the instance.
+    pub instance: Uid,
+
+    /// Path of the interface of the instance.
+    pub path: OwnedPath,
+
+    /// Version of the interface of the instance.
+    pub version: Version,
+
+    /// State of the thread that implements the instance after the change.
+    pub state: State,
+}
+
+impl Input for InstanceEvent {
+    fn interface() -> &'static Rc<Interface> {
+        kobzar_env().network().system_interface(SystemInterface::InstanceEvent)
+    }
+
+    fn from_msg_bytes(b: &[u8]) -> Self {
+        decode(b)
+    }
+}
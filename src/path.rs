@@ -13,65 +13,132 @@
 
 use smallvec::SmallVec;
 use crate::{kobzar_env, KobzarEnv};
-use crate::thread::{ThreadBuilder, OwnedThread, ThreadBuildError, PerformancePolicy};
+use crate::thread::{ThreadBuilder, OwnedThread, ThreadBuildError, PerformancePolicy, Thread,
+                    OrphanPolicy, OrphanPolicyError, PowerSaveSubscription, ResourceUsage,
+                    OverrunResponse, TypeChangeError, ExpiryResponse, Type, Priority,
+                    PriorityError, State, Publicity, ComputingUnit};
 use alloc::sync::Arc;
 use alloc::rc::Rc;
 use core::time::Duration;
 use crate::msg::{Receiver, Output, Sender, Input, SendError, ReceiveError, MailboxSendError};
-use core::ops::Range;
+use crate::notif::SystemInterface;
+use crate::watchdog::{Watchdog, WatchdogError};
+use crate::timer::{Deadline, TimerError};
+use crate::watch::WatchError;
+use crate::registry::RegistryError;
+use crate::clock::ClockBackend;
+use crate::pattern::PathPattern;
+use crate::version::VersionReq;
 use arrayvec::ArrayVec;
 use alloc::vec::Vec;
+use alloc::boxed::Box;
 use serde::{Serialize, Deserialize, Serializer};
-use alloc::string::String;
+use core::convert::TryFrom;
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::str::FromStr;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
 
 type NodeVec<'a> = ArrayVec<[&'a str; 8]>;
 
+/// Maximum amount of nodes in the path.
+pub const MAX_NODES: usize = 8;
+
 /// Unique identifier of the object inside of the network. These include threads and interfaces.
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Uid(pub u64);
 
-/// Path of some resource. It consists of up to 8 nodes. Path entry
+/// Path of some resource. It consists of up to [MAX_NODES] nodes. Path entry
 /// contains only pointers to string slices and generally Clone operation is
 /// cheap.
 ///
 /// This path is never created by developer and instead it is always provided by
 /// the network to describe existing resources.
+///
+/// [Path], [LocalPath] and [OwnedPath] with the same nodes are equal and have the same hash.
+/// Paths are ordered lexicographically by nodes so a prefix goes before the longer path.
 // TODO impl node types.
-#[derive(Clone, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone)]
 pub struct Path {
     nodes: NodeVec<'static>,
 }
 
-#[derive(Clone, Eq, PartialOrd, Ord, Hash, Deserialize)]
+/// Path that owns its nodes. It is functionally the same as [Path]. Deserialized path is
+/// validated the same way as the parsed one.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawOwnedPath")]
 pub struct OwnedPath {
     nodes: Vec<String>,
 }
 
+/// Path as it is received from the remote system before validation.
+#[derive(Deserialize)]
+struct RawOwnedPath {
+    nodes: Vec<String>,
+}
+
+impl TryFrom<RawOwnedPath> for OwnedPath {
+    type Error = PathParseError;
+
+    fn try_from(raw: RawOwnedPath) -> Result<Self, Self::Error> {
+        if raw.nodes.is_empty() {
+            return Err(PathParseError::Empty);
+        }
+        if raw.nodes.len() > MAX_NODES {
+            return Err(PathParseError::TooManyNodes);
+        }
+        for (index, node) in raw.nodes.iter().enumerate() {
+            check_node(node, index)?;
+        }
+        Ok(OwnedPath { nodes: raw.nodes })
+    }
+}
+
 impl Path {
     pub fn nodes(&self) -> &NodeVec<'static> {
         &self.nodes
     }
-}
 
-impl PartialEq for Path {
-    fn eq(&self, other: &Self) -> bool {
-        for i in 0..self.nodes.len() {
-            if self.nodes[i] != other.nodes[i] {
-                return false;
-            }
+    pub(crate) fn from_local(p: LocalPath<'static>) -> Path {
+        Path {
+            nodes: p.nodes,
         }
-        true
     }
-}
 
-impl PartialEq for OwnedPath {
-    fn eq(&self, other: &Self) -> bool {
-        for i in 0..self.nodes.len() {
-            if self.nodes[i] != other.nodes[i] {
-                return false;
-            }
-        }
-        true
+    /// Path without its last node. None is returned for the empty path.
+    pub fn parent(&self) -> Option<Path> {
+        LocalPath::from(self).parent().map(Path::from_local)
+    }
+
+    /// Local path with the nodes of other path appended. None is returned if the result
+    /// would have more than [MAX_NODES] nodes.
+    pub fn join<'a>(&self, other: &LocalPath<'a>) -> Option<LocalPath<'a>> {
+        let local: LocalPath<'a> = LocalPath {
+            nodes: self.nodes.iter().cloned().collect(),
+        };
+        local.join(other)
+    }
+
+    /// Whether path starts with all nodes of the prefix. Any path starts with itself.
+    pub fn starts_with(&self, prefix: &LocalPath) -> bool {
+        LocalPath::from(self).starts_with(prefix)
+    }
+
+    /// Whether path is nested in the ancestor path. Path is not a descendant of itself.
+    pub fn is_descendant_of(&self, ancestor: &LocalPath) -> bool {
+        LocalPath::from(self).is_descendant_of(ancestor)
+    }
+
+    /// The longest path both paths start with.
+    pub fn common_ancestor(&self, other: &LocalPath) -> Path {
+        Path::from_local(LocalPath::from(self).common_ancestor(other))
+    }
+
+    /// Iterate over ancestors of the path starting from its parent and ending with
+    /// the empty path.
+    pub fn ancestors(&self) -> impl Iterator<Item=Path> {
+        LocalPath::from(self).ancestors().map(Path::from_local)
     }
 }
 
@@ -84,8 +151,8 @@ impl<'a> From<&'a Path> for LocalPath<'static> {
 }
 
 /// Local path is a path constructed from local str slices. It is functionally the same as
-/// [Path].
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
+/// [Path]. Local path can be [parsed](LocalPath::parse) from a string.
+#[derive(Clone)]
 pub struct LocalPath<'a> {
     nodes: NodeVec<'a>,
 }
@@ -100,6 +167,66 @@ impl<'a> LocalPath<'a> {
             nodes,
         }
     }
+
+    /// Path without its last node. None is returned for the empty path.
+    pub fn parent(&self) -> Option<LocalPath<'a>> {
+        let mut nodes = self.nodes.clone();
+        nodes.pop().map(|_| LocalPath { nodes })
+    }
+
+    /// Path with the nodes of other path appended. None is returned if the result would have
+    /// more than [MAX_NODES] nodes.
+    pub fn join(&self, other: &LocalPath<'a>) -> Option<LocalPath<'a>> {
+        let mut nodes = self.nodes.clone();
+        for node in other.nodes.iter() {
+            nodes.try_push(*node).ok()?;
+        }
+        Some(LocalPath { nodes })
+    }
+
+    /// Whether path starts with all nodes of the prefix. Any path starts with itself.
+    pub fn starts_with(&self, prefix: &LocalPath) -> bool {
+        self.nodes.len() >= prefix.nodes.len()
+            && self.nodes.iter().zip(prefix.nodes.iter()).all(|(a, b)| a == b)
+    }
+
+    /// Whether path is nested in the ancestor path. Path is not a descendant of itself.
+    pub fn is_descendant_of(&self, ancestor: &LocalPath) -> bool {
+        self.nodes.len() > ancestor.nodes.len() && self.starts_with(ancestor)
+    }
+
+    /// The longest path both paths start with.
+    pub fn common_ancestor(&self, other: &LocalPath) -> LocalPath<'a> {
+        let len = self.nodes.iter().zip(other.nodes.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        LocalPath {
+            nodes: self.nodes.iter().take(len).cloned().collect(),
+        }
+    }
+
+    /// Iterate over ancestors of the path starting from its parent and ending with
+    /// the empty path.
+    pub fn ancestors(&self) -> Ancestors<'a> {
+        Ancestors {
+            next: self.parent(),
+        }
+    }
+}
+
+/// Iterator over ancestors of the path. See [LocalPath::ancestors].
+pub struct Ancestors<'a> {
+    next: Option<LocalPath<'a>>,
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = LocalPath<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+        self.next = current.parent();
+        Some(current)
+    }
 }
 
 impl<'a> Serialize for LocalPath<'a> {
@@ -110,8 +237,240 @@ impl<'a> Serialize for LocalPath<'a> {
     }
 }
 
-/// Version of the app in form `major.minor.patch`.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+impl Path {
+    fn node_iter(&self) -> impl ExactSizeIterator<Item=&str> + '_ {
+        self.nodes.iter().cloned()
+    }
+}
+
+impl<'a> LocalPath<'a> {
+    fn node_iter(&self) -> impl ExactSizeIterator<Item=&str> + '_ {
+        self.nodes.iter().cloned()
+    }
+}
+
+impl OwnedPath {
+    fn node_iter(&self) -> impl ExactSizeIterator<Item=&str> + '_ {
+        self.nodes.iter().map(|n| n.as_str())
+    }
+}
+
+fn hash_nodes<'a, H: Hasher>(nodes: impl ExactSizeIterator<Item=&'a str>, state: &mut H) {
+    state.write_usize(nodes.len());
+    for node in nodes {
+        node.hash(state);
+    }
+}
+
+/// Implement `Eq`, `Ord` and `Hash` for the path type by comparing its nodes.
+macro_rules! path_traits {
+    ($([$($gen:tt)*] $t:ty);* $(;)?) => {$(
+        impl<$($gen)*> PartialEq for $t {
+            fn eq(&self, other: &Self) -> bool {
+                self.node_iter().eq(other.node_iter())
+            }
+        }
+
+        impl<$($gen)*> Eq for $t {}
+
+        impl<$($gen)*> PartialOrd for $t {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<$($gen)*> Ord for $t {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.node_iter().cmp(other.node_iter())
+            }
+        }
+
+        impl<$($gen)*> Hash for $t {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                hash_nodes(self.node_iter(), state)
+            }
+        }
+    )*};
+}
+
+/// Implement comparisons of the nodes between different path types.
+macro_rules! path_cmp {
+    ($([$($gen:tt)*] $t:ty, $other:ty);* $(;)?) => {$(
+        impl<$($gen)*> PartialEq<$other> for $t {
+            fn eq(&self, other: &$other) -> bool {
+                self.node_iter().eq(other.node_iter())
+            }
+        }
+
+        impl<$($gen)*> PartialOrd<$other> for $t {
+            fn partial_cmp(&self, other: &$other) -> Option<Ordering> {
+                Some(self.node_iter().cmp(other.node_iter()))
+            }
+        }
+    )*};
+}
+
+path_traits! {
+    [] Path;
+    ['a] LocalPath<'a>;
+    [] OwnedPath;
+}
+
+path_cmp! {
+    ['a] Path, LocalPath<'a>;
+    ['a] LocalPath<'a>, Path;
+    [] Path, OwnedPath;
+    [] OwnedPath, Path;
+    ['a] OwnedPath, LocalPath<'a>;
+    ['a] LocalPath<'a>, OwnedPath;
+}
+
+/// Error of parsing the path from a string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathParseError {
+    /// String contains no nodes.
+    Empty,
+
+    /// Node with given index is empty.
+    EmptyNode {
+        index: usize,
+    },
+
+    /// Node with given index contains a character that is not allowed. Nodes may contain
+    /// only ASCII letters, digits, `_` and `-`.
+    InvalidChar {
+        index: usize,
+        ch: char,
+    },
+
+    /// Path has more than [MAX_NODES] nodes.
+    TooManyNodes,
+
+    /// Both `.` and `/` are used as separators.
+    MixedSeparators,
+
+    /// Node with given index is not a valid [pattern](crate::pattern) node.
+    InvalidPattern {
+        index: usize,
+    },
+}
+
+impl fmt::Display for PathParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PathParseError::Empty => write!(f, "path is empty"),
+            PathParseError::EmptyNode { index } => write!(f, "node {} is empty", index),
+            PathParseError::InvalidChar { index, ch } =>
+                write!(f, "node {} contains invalid character {:?}", index, ch),
+            PathParseError::TooManyNodes => write!(f, "path has more than {} nodes", MAX_NODES),
+            PathParseError::MixedSeparators => write!(f, "path mixes '.' and '/' separators"),
+            PathParseError::InvalidPattern { index } =>
+                write!(f, "node {} is not a valid pattern", index),
+        }
+    }
+}
+
+fn is_node_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_' || ch == '-'
+}
+
+/// Split the path string into nodes. Nodes are separated either by `.` or by `/` but not
+/// by both.
+pub(crate) fn split_nodes(s: &str) -> Result<core::str::Split<'_, char>, PathParseError> {
+    if s.is_empty() {
+        return Err(PathParseError::Empty);
+    }
+    let separator = match (s.contains('.'), s.contains('/')) {
+        (true, true) => return Err(PathParseError::MixedSeparators),
+        (false, true) => '/',
+        _ => '.',
+    };
+    Ok(s.split(separator))
+}
+
+/// Check that the node with given index is not empty and has only valid characters.
+pub(crate) fn check_node(node: &str, index: usize) -> Result<(), PathParseError> {
+    if node.is_empty() {
+        Err(PathParseError::EmptyNode { index })
+    } else if let Some(ch) = node.chars().find(|c| !is_node_char(*c)) {
+        Err(PathParseError::InvalidChar { index, ch })
+    } else {
+        Ok(())
+    }
+}
+
+impl<'a> LocalPath<'a> {
+    /// Parse path from the string. Nodes are separated either by `.` or by `/` like in
+    /// `drivers.usb.hub` or `drivers/usb/hub`.
+    pub fn parse(s: &'a str) -> Result<Self, PathParseError> {
+        let mut nodes = NodeVec::new();
+        for (index, node) in split_nodes(s)?.enumerate() {
+            check_node(node, index)?;
+            nodes.try_push(node).map_err(|_| PathParseError::TooManyNodes)?;
+        }
+        Ok(LocalPath { nodes })
+    }
+
+    /// Copy path nodes to create owned path.
+    pub fn to_owned_path(&self) -> OwnedPath {
+        OwnedPath {
+            nodes: self.nodes.iter().map(|n| n.to_string()).collect(),
+        }
+    }
+}
+
+impl OwnedPath {
+    pub fn nodes(&self) -> &[String] {
+        &self.nodes
+    }
+
+    /// Borrow nodes of this path as a local path.
+    pub fn as_local(&self) -> LocalPath<'_> {
+        LocalPath {
+            nodes: self.nodes.iter().map(|n| n.as_str()).collect(),
+        }
+    }
+}
+
+impl FromStr for OwnedPath {
+    type Err = PathParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LocalPath::parse(s).map(|p| p.to_owned_path())
+    }
+}
+
+fn fmt_nodes<'a>(f: &mut fmt::Formatter, nodes: impl Iterator<Item=&'a str>) -> fmt::Result {
+    for (i, node) in nodes.enumerate() {
+        if i > 0 {
+            f.write_str(".")?;
+        }
+        f.write_str(node)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_nodes(f, self.nodes.iter().cloned())
+    }
+}
+
+impl<'a> fmt::Display for LocalPath<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_nodes(f, self.nodes.iter().cloned())
+    }
+}
+
+impl fmt::Display for OwnedPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_nodes(f, self.nodes.iter().map(|n| n.as_str()))
+    }
+}
+
+/// Version of the app in form `major.minor.patch`. Version can be parsed from and displayed
+/// in this form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Version(pub u32, pub u32, pub u32);
 
 /// Interface instance. Thread ID that is an implementor of selected interface.
@@ -136,11 +495,33 @@ impl InstanceId {
     pub fn uid(&self) -> Uid {
         self.uid
     }
+
+    /// Interface of this instance.
+    pub fn interface(&self) -> &Rc<Interface> {
+        &self.interface
+    }
+
+    /// Whether the interface of this instance is a singleton.
+    pub fn is_singleton(&self) -> bool {
+        self.interface.is_singleton
+    }
+
+    /// Whether the interface of this instance has executable code.
+    pub fn has_executable(&self) -> bool {
+        self.interface.has_executable
+    }
+
+    /// Snapshot of the thread that implements this instance. None is returned if instance
+    /// is no longer known to the network.
+    pub fn thread(&self) -> Option<Thread> {
+        kobzar_env().network().instance_thread(self)
+    }
 }
 
 /// Interface defines a function that should be provided by the server that implements it.
 /// Each thread implements set of interfaces. At least, thread implements an interface that
-/// declares this thread type so it can be instantiated.
+/// declares this thread type so it can be instantiated. Interfaces are declared with
+/// [InterfaceBuilder](crate::registry::InterfaceBuilder).
 #[derive(Clone, Eq)]
 pub struct Interface {
     path: Path,
@@ -181,6 +562,72 @@ impl Interface {
         &self.dependencies
     }
 
+    /// All interfaces this interface transitively depends on in the order they should be
+    /// instantiated. Each dependency goes after its own dependencies. If dependencies form
+    /// a cycle Err is returned with the interface that closes the cycle.
+    pub fn dependency_order(&self) -> Result<Vec<Rc<Interface>>, Rc<Interface>> {
+        let mut order = Vec::new();
+        let mut stack = Vec::new();
+        self.visit(|i| &i.dependencies, &mut stack, &mut order)?;
+        Ok(order)
+    }
+
+    /// All interfaces this interface transitively implements. Each interface goes after
+    /// the interfaces it implements. If `implements` form a cycle Err is returned with the
+    /// interface that closes the cycle.
+    pub fn all_implements(&self) -> Result<Vec<Rc<Interface>>, Rc<Interface>> {
+        let mut order = Vec::new();
+        let mut stack = Vec::new();
+        self.visit(|i| &i.implements, &mut stack, &mut order)?;
+        Ok(order)
+    }
+
+    /// Whether implementer of this interface can serve requests to the interface with
+    /// given path and version, either directly or through any interface it transitively
+    /// implements.
+    pub fn satisfies(&self, path: &Path, version: &VersionReq) -> bool {
+        self.provider(|i| &i.path == path && version.matches(i.version)).is_some()
+    }
+
+    /// Interface with the highest version among this interface and all interfaces it
+    /// transitively implements that satisfies the predicate. Cycles in `implements` are
+    /// skipped.
+    pub(crate) fn provider(&self, mut f: impl FnMut(&Interface) -> bool) -> Option<&Interface> {
+        let mut visited: Vec<&Interface> = Vec::new();
+        let mut pending = Vec::new();
+        let mut best: Option<&Interface> = None;
+        pending.push(self);
+        while let Some(i) = pending.pop() {
+            if visited.contains(&i) {
+                continue;
+            }
+            if f(i) && best.is_none_or(|b| i.version > b.version) {
+                best = Some(i);
+            }
+            visited.push(i);
+            pending.extend(i.implements.iter().map(|i| &**i));
+        }
+        best
+    }
+
+    fn visit<'s>(&'s self, edges: fn(&Interface) -> &Vec<Rc<Interface>>,
+                 stack: &mut Vec<&'s Interface>, order: &mut Vec<Rc<Interface>>)
+                 -> Result<(), Rc<Interface>> {
+        stack.push(self);
+        for next in edges(self) {
+            if stack.iter().any(|i| *i == &**next) {
+                return Err(next.clone());
+            }
+            if order.contains(next) {
+                continue;
+            }
+            next.visit(edges, stack, order)?;
+            order.push(next.clone());
+        }
+        stack.pop();
+        Ok(())
+    }
+
     /// Interfaces that are also implemented by implementers of given interface.
     pub fn implements(&self) -> &Vec<Rc<Interface>> {
         &self.implements
@@ -191,52 +638,278 @@ impl Interface {
     pub fn has_executable(&self) -> bool {
         self.has_executable
     }
+
+    /// Whether both interfaces have the same path and version but are declared differently.
+    /// Such interfaces cannot be registered in the same network.
+    pub fn conflicts_with(&self, other: &Interface) -> bool {
+        self == other && (self.is_singleton != other.is_singleton
+            || self.has_executable != other.has_executable
+            || self.dependencies != other.dependencies
+            || self.implements != other.implements)
+    }
+
+    pub(crate) fn new(path: Path, version: Version, is_singleton: bool, has_executable: bool,
+                      dependencies: Vec<Rc<Interface>>, implements: Vec<Rc<Interface>>) -> Self {
+        Interface {
+            path,
+            version,
+            is_singleton,
+            has_executable,
+            dependencies,
+            implements,
+        }
+    }
+}
+
+/// Path of the interfaces to search for.
+pub enum PathQuery<'a> {
+    /// Interface with exactly this path.
+    Exact(LocalPath<'a>),
+
+    /// All interfaces which paths match the pattern.
+    Pattern(PathPattern),
+}
+
+impl<'a> PathQuery<'a> {
+    /// Whether the path satisfies this query.
+    pub fn matches(&self, path: &Path) -> bool {
+        match self {
+            PathQuery::Exact(p) => path == p,
+            PathQuery::Pattern(p) => p.matches_path(path),
+        }
+    }
 }
 
-/// Request to find instances of given interface with specific version.
+/// Request to find instances of given interface with specific version. Instances can be
+/// further filtered by the properties of the threads that implement them.
 pub struct FindInstanceRequest<'a> {
-    path: LocalPath<'a>,
-    version: Option<Range<Version>>,
+    path: PathQuery<'a>,
+    version: Option<VersionReq>,
+    /// Accepted thread states as a bit mask indexed by [State] discriminant.
+    states: Option<u8>,
+    publicity: Option<Publicity>,
+    performance: Option<PerformancePolicy>,
+    computing_unit: Option<ComputingUnit>,
+    owner: Option<Uid>,
+    has_executable: Option<bool>,
+    offset: usize,
+    limit: Option<usize>,
 }
 
 impl<'a> FindInstanceRequest<'a> {
     /// Create new request to search for interface with given path.
     pub fn new(path: LocalPath<'a>) -> Self {
+        Self::with_query(PathQuery::Exact(path))
+    }
+
+    /// Create new request to search for all interfaces which paths match the pattern.
+    pub fn matching(pattern: PathPattern) -> Self {
+        Self::with_query(PathQuery::Pattern(pattern))
+    }
+
+    fn with_query(path: PathQuery<'a>) -> Self {
         FindInstanceRequest {
             path,
             version: None,
+            states: None,
+            publicity: None,
+            performance: None,
+            computing_unit: None,
+            owner: None,
+            has_executable: None,
+            offset: 0,
+            limit: None,
         }
     }
 
-    /// Search for implementers that match version constraints.
-    pub fn with_version(mut self, version: Range<Version>) -> Self {
-        self.version = Some(version);
+    /// Search for implementers that match version constraints. Version range can also be
+    /// given as a requirement.
+    pub fn with_version(mut self, version: impl Into<VersionReq>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Search only for instances which thread is in one of the given states.
+    pub fn with_states(mut self, states: &[State]) -> Self {
+        self.states = Some(states.iter().fold(0, |mask, s| mask | state_bit(*s)));
+        self
+    }
+
+    /// Search only for instances with given publicity.
+    pub fn with_publicity(mut self, publicity: Publicity) -> Self {
+        self.publicity = Some(publicity);
+        self
+    }
+
+    /// Search only for instances running with given performance policy.
+    pub fn with_performance_policy(mut self, policy: PerformancePolicy) -> Self {
+        self.performance = Some(policy);
+        self
+    }
+
+    /// Search only for instances running on given Computing Unit.
+    pub fn on_computing_unit(mut self, unit: ComputingUnit) -> Self {
+        self.computing_unit = Some(unit);
         self
     }
 
-    /// Execute request and find all implementers.
+    /// Search only for instances owned by the thread with given UID.
+    pub fn owned_by(mut self, owner: Uid) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Search only for instances which interface has or has no executable code.
+    pub fn with_executable(mut self, has_executable: bool) -> Self {
+        self.has_executable = Some(has_executable);
+        self
+    }
+
+    /// Skip given amount of the best matching instances.
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Return at most given amount of instances.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Execute request and find all implementers. All matching instances are returned even
+    /// if there are more of them than the inline capacity of the vector unless the limit
+    /// is set. Instances are sorted by the best match, that is, the highest version of
+    /// the interface that satisfies the request goes first.
     pub fn find(&self) -> SmallVec<[Arc<InstanceId>; 16]> {
-        kobzar_env().network().find_package_instances(self)
+        self.find_page().instances
+    }
+
+    /// Execute request and find one page of implementers selected by offset and limit.
+    pub fn find_page(&self) -> InstancePage {
+        let mut page = kobzar_env().network().find_package_instances(self);
+        page.instances.sort_by_key(|i| {
+            core::cmp::Reverse(self.provider(i.interface()).map(|p| p.version()))
+        });
+        page
+    }
+
+    /// Whether the interface satisfies path, version and executable filters of this request.
+    /// Path and version are matched against the interface itself and all interfaces it
+    /// transitively implements.
+    pub fn matches_interface(&self, interface: &Interface) -> bool {
+        self.provider(interface).is_some()
+    }
+
+    /// Interface through which the request is satisfied. It is the interface itself or
+    /// the one it implements with the highest matching version.
+    fn provider<'i>(&self, interface: &'i Interface) -> Option<&'i Interface> {
+        if self.has_executable.is_some_and(|e| e != interface.has_executable()) {
+            return None;
+        }
+        interface.provider(|i| self.path.matches(i.path())
+            && self.version.as_ref().is_none_or(|v| v.matches(i.version())))
+    }
+
+    /// Whether the thread satisfies all filters of this request.
+    pub fn matches_thread(&self, thread: &Thread) -> bool {
+        self.matches_interface(thread.instance().interface())
+            && self.states.is_none_or(|mask| mask & state_bit(thread.state()) != 0)
+            && self.publicity.is_none_or(|p| p == thread.publicity())
+            && self.performance.is_none_or(|p| p == thread.performance_policy())
+            && self.computing_unit.is_none_or(|c| c == thread.computing_unit())
+            && self.owner.is_none_or(|o| thread.parent() == Some(o))
     }
 
     /// Path of the interface.
-    pub fn path(&self) -> &LocalPath<'a> {
+    pub fn path(&self) -> &PathQuery<'a> {
         &self.path
     }
 
     /// Version constraints of the interface.
-    pub fn version(&self) -> &Option<Range<Version>> {
+    pub fn version(&self) -> &Option<VersionReq> {
         &self.version
     }
+
+    /// Amount of the best matching instances to skip.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Maximum amount of instances to return.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+}
+
+fn state_bit(state: State) -> u8 {
+    1 << state as u8
+}
+
+/// Page of the instances found by [FindInstanceRequest].
+pub struct InstancePage {
+    instances: SmallVec<[Arc<InstanceId>; 16]>,
+    offset: usize,
+    total: usize,
+}
+
+impl InstancePage {
+    /// Instances on this page sorted by the best match.
+    pub fn instances(&self) -> &[Arc<InstanceId>] {
+        &self.instances
+    }
+
+    /// Take the instances of this page.
+    pub fn into_instances(self) -> SmallVec<[Arc<InstanceId>; 16]> {
+        self.instances
+    }
+
+    /// Amount of all matching instances regardless of offset and limit.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Offset of the next page. None is returned if this page is the last one.
+    pub fn next_offset(&self) -> Option<usize> {
+        let next = self.offset + self.instances.len();
+        if next < self.total && !self.instances.is_empty() {
+            Some(next)
+        } else {
+            None
+        }
+    }
 }
 
 pub(crate) trait Network {
-    /// Find instances that have this package name.
-    fn find_package_instances(&self, find: &FindInstanceRequest)
-                              -> SmallVec<[Arc<InstanceId>; 16]>;
+    /// Find instances that satisfy the request. Instances are sorted by the best match before
+    /// offset and limit are applied.
+    fn find_package_instances(&self, find: &FindInstanceRequest) -> InstancePage;
 
+    /// Thread that implements given instance. None is returned if instance is not known.
+    fn instance_thread(&self, instance: &InstanceId) -> Option<Thread>;
+
+    /// Create the thread. If implemented interface is a singleton the network must check
+    /// the running implementer and take the slot in one atomic step, returning
+    /// [SingletonRunning](ThreadBuildError::SingletonRunning) if it is already taken. Check
+    /// made by the builder beforehand is only a fast path and does not prevent races.
     fn create_thread(&self, t: &ThreadBuilder) -> Result<OwnedThread, ThreadBuildError>;
 
+    /// Running implementer of the singleton interface. None is returned if interface is not
+    /// a singleton or no implementer is running. The slot is released as soon as the
+    /// implementer dies, whether it ceased, was killed or was stopped by the orphan policy
+    /// of its owner.
+    fn singleton_holder(&self, imp: &Interface) -> Option<Thread>;
+
+    /// Make the thread a child of given parent in the thread tree.
+    fn adopt(&self, parent: &OwnedThread, child: OwnedThread);
+
+    /// Create the thread that replaces the dead one. Singleton slot is checked and taken
+    /// atomically like in [create_thread](Network::create_thread). The slot of the replaced
+    /// thread is free only if that thread is actually dead, otherwise
+    /// [SingletonRunning](ThreadBuildError::SingletonRunning) is returned.
+    fn recreate_thread(&self, dead: &OwnedThread, t: &ThreadBuilder)
+                       -> Result<OwnedThread, ThreadBuildError>;
+
     fn allow_run(&self, t: &OwnedThread);
 
     fn request_pause(&self, t: &OwnedThread);
@@ -247,11 +920,66 @@ pub(crate) trait Network {
 
     fn sleep(&self, t: &OwnedThread, duration: Duration);
 
+    fn clock(&self) -> &dyn ClockBackend;
+
+    fn set_clock_backend(&mut self, backend: Box<dyn ClockBackend>);
+
+    /// Start timer of the current thread.
+    fn start_timer(&self, deadline: &Deadline, period: Option<Duration>, tag: u64)
+                   -> Result<Uid, TimerError>;
+
+    fn cancel_timer(&self, timer: Uid) -> bool;
+
+    /// Start watching instances that satisfy the request. Offset and limit are ignored.
+    /// Returns UID of the watch and instances that matched when it was started.
+    fn start_watch(&self, find: &FindInstanceRequest) -> Result<(Uid, InstancePage), WatchError>;
+
+    fn cancel_watch(&self, watch: Uid) -> bool;
+
+    /// Register interface in the network. Registering the same declaration again returns
+    /// the interface that is already registered.
+    fn register_interface(&self, interface: Interface) -> Result<Rc<Interface>, RegistryError>;
+
+    /// Publish registered interface as served by the current thread.
+    fn publish_interface(&self, interface: &Rc<Interface>)
+                         -> Result<Rc<InstanceId>, RegistryError>;
+
     fn set_performance_policy(&self, t: &OwnedThread, policy: PerformancePolicy)
                               -> Result<(), PerformancePolicy>;
 
+    fn set_powersave_subscription(&self, t: &OwnedThread, subscription: PowerSaveSubscription);
+
+    fn set_type(&self, t: &OwnedThread, ty: &Type) -> Result<(), TypeChangeError>;
+
+    fn set_priority(&self, t: &OwnedThread, priority: Priority) -> Result<(), PriorityError>;
+
+    fn set_priority_ceiling(&self, t: &OwnedThread, ceiling: Priority)
+                            -> Result<(), PriorityError>;
+
+    fn respond_to_overrun(&self, t: &OwnedThread, response: &OverrunResponse)
+                          -> Result<(), TypeChangeError>;
+
+    fn respond_to_expiry(&self, t: &OwnedThread, response: &ExpiryResponse)
+                         -> Result<(), TypeChangeError>;
+
+    fn set_watchdog(&self, t: &OwnedThread, watchdog: Option<&Watchdog>)
+                    -> Result<(), WatchdogError>;
+
+    /// Heartbeat of the current thread.
+    fn heartbeat(&self);
+
+    fn set_orphan_policy(&self, t: &OwnedThread, policy: OrphanPolicy)
+                         -> Result<(), OrphanPolicyError>;
+
+    fn children(&self, t: &Thread) -> Vec<Thread>;
+
+    fn resource_usage(&self, t: &Thread) -> ResourceUsage;
+
     fn current_thread(&self) -> &'static mut OwnedThread;
 
+    /// Startup arguments of the current thread.
+    fn startup_args(&self) -> Option<&'static [u8]>;
+
     fn send<O: Output>(&self, sender: &Sender<O>, msg: &O) -> Result<(), MailboxSendError>;
 
     fn send_when_available<O: Output>(&self, _: &Sender<O>, _: &O) -> Result<(), SendError>;
@@ -277,6 +1005,8 @@ pub(crate) trait Network {
     unsafe fn new_receiver_sync_for<I: Input>(&self, time: Duration, int: &Rc<Interface>)
                                               -> Option<Receiver<I>>;
 
+    fn system_interface(&self, interface: SystemInterface) -> &'static Rc<Interface>;
+
     fn has_incoming(&self) -> bool;
 
     fn wait_any<'a>(&self, interfaces: impl Iterator<Item=&'a Interface>);
@@ -284,3 +1014,256 @@ pub(crate) trait Network {
     fn wait_any_for<'a>(&self, wait: Duration, interfaces: impl Iterator<Item=&'a Interface>)
                         -> Option<()>;
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    extern crate std;
+
+    use super::*;
+    use alloc::vec;
+
+    pub(crate) fn interface(path: &'static str, version: Version,
+                            dependencies: Vec<Rc<Interface>>, implements: Vec<Rc<Interface>>)
+                            -> Rc<Interface> {
+        Rc::new(Interface {
+            path: Path::from_local(LocalPath::parse(path).ok().unwrap()),
+            version,
+            is_singleton: false,
+            has_executable: true,
+            dependencies,
+            implements,
+        })
+    }
+
+    fn names(order: &[Rc<Interface>]) -> Vec<&'static str> {
+        order.iter().map(|i| i.path().nodes()[0]).collect()
+    }
+
+    #[test]
+    fn parse_local_path() {
+        let dotted = LocalPath::parse("drivers.usb.hub-2").unwrap();
+        let slashed = LocalPath::parse("drivers/usb/hub-2").unwrap();
+        assert!(dotted == slashed);
+        assert_eq!(dotted.nodes().as_slice(), ["drivers", "usb", "hub-2"]);
+        assert_eq!(dotted.to_string(), "drivers.usb.hub-2");
+    }
+
+    #[test]
+    fn parse_invalid_local_path() {
+        assert_eq!(LocalPath::parse("").err(), Some(PathParseError::Empty));
+        assert_eq!(LocalPath::parse("a..b").err(), Some(PathParseError::EmptyNode { index: 1 }));
+        assert_eq!(LocalPath::parse("a.b/c").err(), Some(PathParseError::MixedSeparators));
+        assert_eq!(LocalPath::parse("a.b c").err(),
+                   Some(PathParseError::InvalidChar { index: 1, ch: ' ' }));
+        assert_eq!(LocalPath::parse("a.b.c.d.e.f.g.h").map(|p| p.nodes().len()), Ok(8));
+        assert_eq!(LocalPath::parse("a.b.c.d.e.f.g.h.i").err(),
+                   Some(PathParseError::TooManyNodes));
+    }
+
+    #[test]
+    fn deserialize_owned_path() {
+        let decode = |nodes: &[&str]| {
+            let nodes: Vec<String> = nodes.iter().map(|n| n.to_string()).collect();
+            postcard::from_bytes::<OwnedPath>(&postcard::to_allocvec(&nodes).unwrap())
+                .map(|p| p.to_string())
+        };
+        assert_eq!(decode(&["app", "logger"]).ok(), Some("app.logger".to_string()));
+        assert!(decode(&[]).is_err());
+        assert!(decode(&["a b"]).is_err());
+        assert!(decode(&["a", "b", "c", "d", "e", "f", "g", "h", "i"]).is_err());
+
+        let path: OwnedPath = "a.b".parse().unwrap();
+        let bytes = postcard::to_allocvec(&path).unwrap();
+        assert!(postcard::from_bytes::<OwnedPath>(&bytes).unwrap() == path);
+    }
+
+    #[test]
+    fn owned_path_round_trip() {
+        let path: OwnedPath = "app/logger".parse().unwrap();
+        assert_eq!(path.to_string(), "app.logger");
+        assert_eq!(path.as_local().nodes().as_slice(), ["app", "logger"]);
+    }
+
+    /// All paths of up to 3 nodes made of nodes that are prefixes of each other, so that
+    /// both node and path prefixes are covered.
+    fn all_paths() -> Vec<Path> {
+        const NODES: [&str; 3] = ["a", "ab", "b"];
+        let mut paths = vec![Path { nodes: NodeVec::new() }];
+        let mut last = paths.clone();
+        for _ in 0..3 {
+            let mut next = Vec::new();
+            for p in &last {
+                for n in NODES.iter() {
+                    let mut p = p.clone();
+                    p.nodes.push(n);
+                    next.push(p);
+                }
+            }
+            paths.extend(next.iter().cloned());
+            last = next;
+        }
+        paths
+    }
+
+    fn hash_of<T: Hash>(t: &T) -> u64 {
+        use std::collections::hash_map::DefaultHasher;
+        let mut hasher = DefaultHasher::new();
+        t.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn path_eq_and_ord_laws() {
+        let paths = all_paths();
+        for a in &paths {
+            assert!(a == a);
+            for b in &paths {
+                assert_eq!(a == b, b == a);
+                assert_eq!(a == b, a.cmp(b) == Ordering::Equal);
+                assert_eq!(a.cmp(b), b.cmp(a).reverse());
+                assert_eq!(a.partial_cmp(b), Some(a.cmp(b)));
+                if a == b {
+                    assert_eq!(hash_of(a), hash_of(b));
+                }
+                for c in &paths {
+                    if a <= b && b <= c {
+                        assert!(a <= c);
+                    }
+                    if a == b && b == c {
+                        assert!(a == c);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn prefix_is_not_equal() {
+        let short = LocalPath::parse("a.b").unwrap();
+        let long = LocalPath::parse("a.b.c").unwrap();
+        assert!(short != long);
+        assert!(long != short);
+        assert!(short < long);
+    }
+
+    #[test]
+    fn path_types_agree() {
+        let paths = all_paths();
+        for a in &paths {
+            let a_local = LocalPath::from(a);
+            let a_owned = a_local.to_owned_path();
+            assert!(*a == a_local);
+            assert!(a_local == *a);
+            assert!(*a == a_owned);
+            assert!(a_owned == a_local);
+            assert_eq!(hash_of(a), hash_of(&a_local));
+            assert_eq!(hash_of(a), hash_of(&a_owned));
+            for b in &paths {
+                let b_local = LocalPath::from(b);
+                let b_owned = b_local.to_owned_path();
+                let expected = a.partial_cmp(b);
+                assert_eq!(a_local.partial_cmp(&b_local), expected);
+                assert_eq!(a_owned.partial_cmp(&b_owned), expected);
+                assert_eq!(a.partial_cmp(&b_local), expected);
+                assert_eq!(a_local.partial_cmp(b), expected);
+                assert_eq!(a.partial_cmp(&b_owned), expected);
+                assert_eq!(a_owned.partial_cmp(b), expected);
+                assert_eq!(a_local.partial_cmp(&b_owned), expected);
+                assert_eq!(a_owned.partial_cmp(&b_local), expected);
+                assert_eq!(a == b, a_local == b_owned);
+            }
+        }
+    }
+
+    #[test]
+    fn path_hierarchy() {
+        let hub = LocalPath::parse("drivers.usb.hub").unwrap();
+        let usb = LocalPath::parse("drivers.usb").unwrap();
+        let net = LocalPath::parse("drivers.net").unwrap();
+
+        assert!(hub.parent() == Some(usb.clone()));
+        assert!(LocalPath::parse("a").unwrap().parent().unwrap().nodes().is_empty());
+        assert!(hub.starts_with(&usb) && hub.starts_with(&hub));
+        assert!(hub.is_descendant_of(&usb) && !hub.is_descendant_of(&hub));
+        assert!(!hub.is_descendant_of(&net));
+        assert_eq!(hub.common_ancestor(&net).to_string(), "drivers");
+        assert!(usb.join(&LocalPath::parse("hub").unwrap()).unwrap() == hub);
+
+        let ancestors: Vec<_> = hub.ancestors().map(|p| p.to_string()).collect();
+        assert_eq!(ancestors, ["drivers.usb", "drivers", ""]);
+
+        let long = LocalPath::parse("a.b.c.d.e").unwrap();
+        assert!(long.join(&long).is_none());
+    }
+
+    #[test]
+    fn dependency_order_is_topological() {
+        let log = interface("log", Version(1, 0, 0), vec![], vec![]);
+        let fs = interface("fs", Version(1, 0, 0), vec![log.clone()], vec![]);
+        let net = interface("net", Version(1, 0, 0), vec![log.clone()], vec![]);
+        let app = interface("app", Version(1, 0, 0), vec![fs, net, log], vec![]);
+
+        assert_eq!(names(&app.dependency_order().ok().unwrap()), ["log", "fs", "net"]);
+    }
+
+    #[test]
+    fn dependency_cycle_is_detected() {
+        let app_again = interface("app", Version(1, 0, 0), vec![], vec![]);
+        let fs = interface("fs", Version(1, 0, 0), vec![app_again], vec![]);
+        let app = interface("app", Version(1, 0, 0), vec![fs], vec![]);
+
+        let cycle = app.dependency_order().err().unwrap();
+        assert_eq!(cycle.path().nodes()[0], "app");
+    }
+
+    #[test]
+    #[allow(clippy::arc_with_non_send_sync)]
+    fn page_next_offset() {
+        let instance = Arc::new(InstanceId {
+            interface: interface("a", Version(1, 0, 0), vec![], vec![]),
+            uid: Uid(0),
+        });
+        let page = |offset, len, total| InstancePage {
+            instances: core::iter::repeat_n(instance.clone(), len).collect(),
+            offset,
+            total,
+        };
+        assert_eq!(page(0, 2, 5).next_offset(), Some(2));
+        assert_eq!(page(2, 2, 5).next_offset(), Some(4));
+        assert_eq!(page(4, 1, 5).next_offset(), None);
+        assert_eq!(page(5, 0, 5).next_offset(), None);
+        assert_eq!(page(9, 0, 5).next_offset(), None);
+    }
+
+    #[test]
+    fn implements_graph() {
+        let log = interface("log", Version(1, 2, 0), vec![], vec![]);
+        let sink = interface("sink", Version(2, 0, 0), vec![], vec![log]);
+        let file = interface("file", Version(1, 0, 0), vec![], vec![sink.clone()]);
+
+        let closure = file.all_implements().ok().unwrap();
+        assert_eq!(names(&closure), vec!["log", "sink"]);
+
+        let log_path = Path::from_local(LocalPath::parse("log").ok().unwrap());
+        assert!(file.satisfies(&log_path, &VersionReq::compatible(Version(1, 1, 0))));
+        assert!(!file.satisfies(&log_path, &VersionReq::compatible(Version(2, 0, 0))));
+        assert!(!sink.satisfies(file.path(), &VersionReq::any()));
+
+        let find = FindInstanceRequest::new(LocalPath::parse("log").ok().unwrap())
+            .with_version(VersionReq::compatible(Version(1, 0, 0)));
+        assert!(find.matches_interface(&file));
+        assert_eq!(find.provider(&file).map(|i| i.version()), Some(Version(1, 2, 0)));
+        assert!(!find.with_executable(false).matches_interface(&file));
+    }
+
+    #[test]
+    fn implements_cycle() {
+        let a = interface("a", Version(1, 0, 0), vec![], vec![]);
+        let b = interface("b", Version(1, 0, 0), vec![], vec![a]);
+        let a = interface("a", Version(1, 0, 0), vec![], vec![b]);
+
+        let cycle = a.all_implements().err().unwrap();
+        assert_eq!(cycle.path().nodes()[0], "a");
+        assert!(a.satisfies(a.path(), &VersionReq::any()));
+    }
+}
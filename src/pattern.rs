@@ -0,0 +1,299 @@
+//! Path patterns are used to find resources which paths are not known exactly. Pattern
+//! consists of nodes separated by `.` or `/` like the path does. Besides plain nodes it can
+//! contain:
+//!
+//! * `*` that matches any single node;
+//! * `**` that matches any amount of nodes, including none;
+//! * `{a,b}` that matches any of the listed nodes.
+//!
+//! For example, `drivers.*` matches all direct children of `drivers` and `**.logger`
+//! matches any path ending with `logger`.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::convert::TryFrom;
+use core::str::FromStr;
+use serde::{Serialize, Deserialize};
+use crate::path::{LocalPath, Path, PathParseError, MAX_NODES, split_nodes, check_node};
+
+/// Single node of the [PathPattern].
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PatternNode {
+    /// Node with exactly this name.
+    Exact(String),
+
+    /// Any single node.
+    AnyNode,
+
+    /// Any amount of nodes, including none.
+    AnyDepth,
+
+    /// Any of the listed nodes.
+    OneOf(Vec<String>),
+}
+
+impl PatternNode {
+    fn matches(&self, node: &str) -> bool {
+        match self {
+            PatternNode::Exact(s) => s == node,
+            PatternNode::AnyNode => true,
+            PatternNode::AnyDepth => true,
+            PatternNode::OneOf(alternatives) => alternatives.iter().any(|s| s == node),
+        }
+    }
+
+    fn parse(s: &str, index: usize) -> Result<Self, PathParseError> {
+        let check = |node: &str| check_node(node, index).map(|_| node.to_string());
+
+        match s {
+            "*" => Ok(PatternNode::AnyNode),
+            "**" => Ok(PatternNode::AnyDepth),
+            _ if s.starts_with('{') => {
+                if !s.ends_with('}') || s.len() < 2 {
+                    return Err(PathParseError::InvalidPattern { index });
+                }
+                let alternatives = s[1..s.len() - 1].split(',')
+                    .map(check)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(PatternNode::OneOf(alternatives))
+            },
+            _ if s.contains(['*', '{', '}', ',']) => {
+                Err(PathParseError::InvalidPattern { index })
+            },
+            _ => check(s).map(PatternNode::Exact),
+        }
+    }
+}
+
+impl fmt::Display for PatternNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternNode::Exact(s) => f.write_str(s),
+            PatternNode::AnyNode => f.write_str("*"),
+            PatternNode::AnyDepth => f.write_str("**"),
+            PatternNode::OneOf(alternatives) => {
+                f.write_str("{")?;
+                for (i, s) in alternatives.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    f.write_str(s)?;
+                }
+                f.write_str("}")
+            },
+        }
+    }
+}
+
+/// Maximum amount of nodes in the pattern. Consecutive `**` are collapsed into one so
+/// the pattern can have at most one `**` around each of its [MAX_NODES] other nodes.
+const MAX_PATTERN_NODES: usize = 2 * MAX_NODES + 1;
+
+/// Pattern that matches paths. Pattern can be serialized to be sent to the remote systems.
+/// Deserialized pattern is validated the same way as the parsed one.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawPattern")]
+pub struct PathPattern {
+    nodes: Vec<PatternNode>,
+}
+
+/// Pattern as it is received from the remote system before validation.
+#[derive(Deserialize)]
+struct RawPattern {
+    nodes: Vec<PatternNode>,
+}
+
+impl TryFrom<RawPattern> for PathPattern {
+    type Error = PathParseError;
+
+    fn try_from(raw: RawPattern) -> Result<Self, Self::Error> {
+        for (index, node) in raw.nodes.iter().enumerate() {
+            match node {
+                PatternNode::Exact(s) => check_node(s, index)?,
+                PatternNode::OneOf(alternatives) => {
+                    if alternatives.is_empty() {
+                        return Err(PathParseError::InvalidPattern { index });
+                    }
+                    for s in alternatives {
+                        check_node(s, index)?;
+                    }
+                },
+                PatternNode::AnyNode | PatternNode::AnyDepth => (),
+            }
+        }
+        PathPattern::new(raw.nodes)
+    }
+}
+
+impl PathPattern {
+    /// Parse the pattern from the string. See [module](self) documentation for the syntax.
+    pub fn parse(s: &str) -> Result<Self, PathParseError> {
+        let nodes = split_nodes(s)?
+            .enumerate()
+            .map(|(index, node)| PatternNode::parse(node, index))
+            .collect::<Result<Vec<_>, _>>()?;
+        PathPattern::new(nodes)
+    }
+
+    /// Collapse consecutive `**` and check the amount of nodes. `**` may match no nodes at
+    /// all so only the nodes that match exactly one path node are limited by [MAX_NODES].
+    fn new(mut nodes: Vec<PatternNode>) -> Result<Self, PathParseError> {
+        nodes.dedup_by(|a, b| *a == PatternNode::AnyDepth && *b == PatternNode::AnyDepth);
+        let single = nodes.iter().filter(|n| **n != PatternNode::AnyDepth).count();
+        if single > MAX_NODES || nodes.len() > MAX_PATTERN_NODES {
+            return Err(PathParseError::TooManyNodes);
+        }
+        Ok(PathPattern { nodes })
+    }
+
+    /// Pattern that matches only given path.
+    pub fn exact(path: &LocalPath) -> Self {
+        PathPattern {
+            nodes: path.nodes().iter().map(|n| PatternNode::Exact(n.to_string())).collect(),
+        }
+    }
+
+    pub fn nodes(&self) -> &[PatternNode] {
+        &self.nodes
+    }
+
+    /// Whether the path matches this pattern.
+    pub fn matches(&self, path: &LocalPath) -> bool {
+        match_nodes(&self.nodes, path.nodes())
+    }
+
+    /// Whether the path matches this pattern.
+    pub fn matches_path(&self, path: &Path) -> bool {
+        match_nodes(&self.nodes, path.nodes())
+    }
+}
+
+/// Match the nodes in a single pass. `reached[i]` tells whether the first `i` pattern nodes
+/// match the nodes consumed so far.
+fn match_nodes(pattern: &[PatternNode], nodes: &[&str]) -> bool {
+    debug_assert!(pattern.len() <= MAX_PATTERN_NODES);
+    let mut reached = [false; MAX_PATTERN_NODES + 1];
+    reached[0] = true;
+    skip_any_depth(pattern, &mut reached);
+    for node in nodes {
+        let mut next = [false; MAX_PATTERN_NODES + 1];
+        for (i, p) in pattern.iter().enumerate() {
+            if !reached[i] {
+                continue;
+            }
+            match p {
+                PatternNode::AnyDepth => next[i] = true,
+                p if p.matches(node) => next[i + 1] = true,
+                _ => (),
+            }
+        }
+        skip_any_depth(pattern, &mut next);
+        reached = next;
+    }
+    reached[pattern.len()]
+}
+
+/// `**` may match no nodes so whatever reaches it also reaches the node after it.
+fn skip_any_depth(pattern: &[PatternNode], reached: &mut [bool]) {
+    for (i, p) in pattern.iter().enumerate() {
+        if reached[i] && *p == PatternNode::AnyDepth {
+            reached[i + 1] = true;
+        }
+    }
+}
+
+impl FromStr for PathPattern {
+    type Err = PathParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PathPattern::parse(s)
+    }
+}
+
+impl fmt::Display for PathPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", node)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        PathPattern::parse(pattern).unwrap().matches(&LocalPath::parse(path).unwrap())
+    }
+
+    #[test]
+    fn single_node_wildcard() {
+        assert!(matches("drivers.*", "drivers.usb"));
+        assert!(!matches("drivers.*", "drivers"));
+        assert!(!matches("drivers.*", "drivers.usb.hub"));
+    }
+
+    #[test]
+    fn multi_node_wildcard() {
+        assert!(matches("**.logger", "logger"));
+        assert!(matches("**.logger", "app.net.logger"));
+        assert!(!matches("**.logger", "app.logger.file"));
+        assert!(matches("drivers.**", "drivers"));
+        assert!(matches("a.**.z", "a.b.c.z"));
+    }
+
+    #[test]
+    fn alternatives() {
+        assert!(matches("drivers.{usb,net}.*", "drivers.net.eth0"));
+        assert!(!matches("drivers.{usb,net}.*", "drivers.gpu.hdmi"));
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        assert_eq!(PathPattern::parse("a.log*").err(),
+                   Some(PathParseError::InvalidPattern { index: 1 }));
+        assert_eq!(PathPattern::parse("{a,b").err(),
+                   Some(PathParseError::InvalidPattern { index: 0 }));
+        assert_eq!(PathPattern::parse("{a,}").err(), Some(PathParseError::EmptyNode { index: 0 }));
+        assert_eq!(PathPattern::parse("a.b.c.d.e.f.g.h.i").err(),
+                   Some(PathParseError::TooManyNodes));
+    }
+
+    #[test]
+    fn any_depth_is_not_counted() {
+        assert!(matches("a.**.b.c.d.e.f.g.h", "a.b.c.d.e.f.g.h"));
+        assert!(matches("**.a.b.c.d.e.f.g.**", "x.a.b.c.d.e.f.g"));
+    }
+
+    #[test]
+    fn many_any_depth() {
+        let s = "**.".repeat(1000) + "x";
+        let pattern = PathPattern::parse(&s).unwrap();
+        assert_eq!(pattern.nodes().len(), 2);
+        assert!(pattern.matches(&LocalPath::parse("a.b.c.d.e.f.g.x").unwrap()));
+        assert!(!pattern.matches(&LocalPath::parse("a.b.c.d.e.f.g.h").unwrap()));
+
+        let raw = (0..1000).map(|_| PatternNode::AnyDepth)
+            .chain(core::iter::once(PatternNode::Exact("x".to_string())))
+            .collect::<Vec<_>>();
+        let bytes = postcard::to_allocvec(&raw).unwrap();
+        let pattern: PathPattern = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(pattern.to_string(), "**.x");
+
+        let raw = alloc::vec![PatternNode::Exact("a b".to_string())];
+        let bytes = postcard::to_allocvec(&raw).unwrap();
+        assert!(postcard::from_bytes::<PathPattern>(&bytes).is_err());
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let pattern = PathPattern::parse("drivers/{usb,net}/**").unwrap();
+        assert_eq!(pattern.to_string(), "drivers.{usb,net}.**");
+        assert!(pattern.to_string().parse::<PathPattern>().unwrap() == pattern);
+    }
+}
@@ -0,0 +1,213 @@
+//! Registry of the interfaces known to the network. Interface is declared with
+//! [InterfaceBuilder] and [registered](InterfaceBuilder::register) before it can be
+//! [published](publish) by the thread that serves it or used as a dependency.
+
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use crate::path::{Interface, InstanceId, LocalPath, Network, Path, Version};
+use crate::{kobzar_env, KobzarEnv};
+
+/// Error returned when interface cannot be registered or published.
+pub enum RegistryError {
+    /// Declaration refers to two different versions of the same interface.
+    ConflictingVersions {
+        first: Rc<Interface>,
+        second: Rc<Interface>,
+    },
+
+    /// Interface with the same path and version but different declaration is already
+    /// registered.
+    Conflict {
+        existing: Rc<Interface>,
+    },
+
+    /// Dependencies or implemented interfaces of the declaration form a cycle that is
+    /// closed by given interface.
+    Cycle {
+        interface: Rc<Interface>,
+    },
+
+    /// Interface is not registered in the network.
+    NotRegistered {
+        interface: Rc<Interface>,
+    },
+
+    /// Thread already serves another version of this interface.
+    AlreadyPublished {
+        existing: Rc<Interface>,
+    },
+
+    /// Thread has no rights to register or publish this interface.
+    NotPermitted,
+}
+
+/// Declaration of the interface.
+pub struct InterfaceBuilder {
+    pub path: LocalPath<'static>,
+    pub version: Version,
+    pub is_singleton: bool,
+    pub has_executable: bool,
+    pub dependencies: Vec<Rc<Interface>>,
+    pub implements: Vec<Rc<Interface>>,
+}
+
+impl InterfaceBuilder {
+    /// Declare interface with given path and version that has no executable, dependencies
+    /// and implemented interfaces.
+    pub fn new(path: LocalPath<'static>, version: Version) -> Self {
+        InterfaceBuilder {
+            path,
+            version,
+            is_singleton: false,
+            has_executable: false,
+            dependencies: Vec::new(),
+            implements: Vec::new(),
+        }
+    }
+
+    /// Allow only one implementer to run at the same time.
+    pub fn singleton(mut self) -> Self {
+        self.is_singleton = true;
+        self
+    }
+
+    /// Interface has executable code to instantiate it as a thread.
+    pub fn executable(mut self) -> Self {
+        self.has_executable = true;
+        self
+    }
+
+    /// Request given interface to be instantiated when implementer of this one is launched.
+    pub fn depending_on(mut self, dependency: Rc<Interface>) -> Self {
+        self.dependencies.push(dependency);
+        self
+    }
+
+    /// Implementers of this interface also implement given one.
+    pub fn implementing(mut self, interface: Rc<Interface>) -> Self {
+        self.implements.push(interface);
+        self
+    }
+
+    /// Check the declaration and create the interface without registering it. All
+    /// transitive dependencies and implemented interfaces together should refer to only one
+    /// version of each interface.
+    pub fn build(self) -> Result<Interface, RegistryError> {
+        let interface = Interface::new(Path::from_local(self.path), self.version,
+                                       self.is_singleton, self.has_executable,
+                                       self.dependencies, self.implements);
+        let cycle = |interface| RegistryError::Cycle { interface };
+        let mut all = interface.dependency_order().map_err(cycle)?;
+        all.extend(interface.all_implements().map_err(cycle)?);
+        check_versions(&all)?;
+        Ok(interface)
+    }
+
+    /// Check the declaration and register the interface in the network.
+    pub fn register(self) -> Result<Rc<Interface>, RegistryError> {
+        let interface = self.build()?;
+        kobzar_env().network().register_interface(interface)
+    }
+}
+
+/// Publish registered interface as served by the current thread. Returns the instance
+/// that can be found by other threads.
+pub fn publish(interface: &Rc<Interface>) -> Result<Rc<InstanceId>, RegistryError> {
+    kobzar_env().network().publish_interface(interface)
+}
+
+fn check_versions(list: &[Rc<Interface>]) -> Result<(), RegistryError> {
+    for (i, first) in list.iter().enumerate() {
+        let conflict = list[i + 1..].iter()
+            .find(|second| first.path() == second.path() && first.version() != second.version());
+        if let Some(second) = conflict {
+            return Err(RegistryError::ConflictingVersions {
+                first: first.clone(),
+                second: second.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use crate::path::tests::interface;
+
+    fn declare(path: &'static str, version: Version) -> InterfaceBuilder {
+        InterfaceBuilder::new(LocalPath::parse(path).ok().unwrap(), version)
+    }
+
+    #[test]
+    fn conflicting_versions() {
+        let log1 = interface("sys.log", Version(1, 0, 0), vec![], vec![]);
+        let log2 = interface("sys.log", Version(2, 0, 0), vec![], vec![]);
+        let fs = interface("sys.fs", Version(1, 0, 0), vec![], vec![]);
+
+        let ok = declare("app", Version(1, 0, 0))
+            .depending_on(log1.clone())
+            .depending_on(fs)
+            .implementing(log1.clone())
+            .build();
+        assert!(ok.is_ok());
+
+        let err = declare("app", Version(1, 0, 0))
+            .depending_on(log1)
+            .depending_on(log2)
+            .build();
+        match err {
+            Err(RegistryError::ConflictingVersions { first, second }) => {
+                assert_eq!(first.version(), Version(1, 0, 0));
+                assert_eq!(second.version(), Version(2, 0, 0));
+            },
+            _ => panic!("conflicting versions were not detected"),
+        }
+    }
+
+    #[test]
+    fn conflicting_transitive_versions() {
+        let log1 = interface("sys.log", Version(1, 0, 0), vec![], vec![]);
+        let log2 = interface("sys.log", Version(2, 0, 0), vec![], vec![]);
+        let fs = interface("sys.fs", Version(1, 0, 0), vec![log2.clone()], vec![]);
+
+        let nested = declare("app", Version(1, 0, 0))
+            .depending_on(log1.clone())
+            .depending_on(fs)
+            .build();
+        assert!(matches!(nested, Err(RegistryError::ConflictingVersions { .. })));
+
+        let mixed = declare("app", Version(1, 0, 0))
+            .depending_on(log1)
+            .implementing(log2)
+            .build();
+        assert!(matches!(mixed, Err(RegistryError::ConflictingVersions { .. })));
+    }
+
+    #[test]
+    fn conflicting_declarations() {
+        let a = declare("a", Version(1, 0, 0)).build().ok().unwrap();
+        let same = declare("a", Version(1, 0, 0)).build().ok().unwrap();
+        let singleton = declare("a", Version(1, 0, 0)).singleton().build().ok().unwrap();
+        let newer = declare("a", Version(1, 1, 0)).singleton().build().ok().unwrap();
+        assert!(!a.conflicts_with(&same));
+        assert!(a.conflicts_with(&singleton));
+        assert!(!a.conflicts_with(&newer));
+    }
+
+    #[test]
+    fn implements_cycle() {
+        let base = interface("base", Version(1, 0, 0), vec![], vec![]);
+        let ext = Rc::new(declare("ext", Version(1, 0, 0))
+            .implementing(base)
+            .build().ok().unwrap());
+        let cyclic = declare("base", Version(1, 0, 0)).implementing(ext).build();
+        match cyclic {
+            Err(RegistryError::Cycle { interface }) => {
+                assert_eq!(interface.path().nodes()[0], "base");
+            },
+            _ => panic!("cycle was not detected"),
+        }
+    }
+}
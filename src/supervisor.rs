@@ -0,0 +1,471 @@
+//! Supervisors keep the services running. Supervisor owns a set of child threads and
+//! restarts them when they die according to the selected [Strategy]. If children die
+//! too often the supervisor gives up and ceases all of them so that the problem can be
+//! handled by the supervisor's own owner.
+
+use crate::thread::{ThreadBuilder, OwnedThread, ThreadBuildError};
+use crate::clock::{self, Instant};
+use core::time::Duration;
+use alloc::vec::Vec;
+use alloc::collections::VecDeque;
+
+/// Defines which children are restarted when one of them dies.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Only the dead child is restarted.
+    OneForOne,
+
+    /// All children are ceased and restarted when any of them dies.
+    OneForAll,
+
+    /// The dead child and all children that were started after it are restarted.
+    RestForOne,
+}
+
+/// Defines whether child should be restarted when it dies.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Restart {
+    /// Child is always restarted.
+    Permanent,
+
+    /// Child is restarted only if it was killed. Ceased child is considered to finish
+    /// its work normally.
+    Transient,
+
+    /// Child is never restarted.
+    Temporary,
+}
+
+/// Maximum amount of restarts that supervisor can make in given period of time. If
+/// this amount is exceeded all children are ceased and supervisor gives up.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Intensity {
+    pub max_restarts: u32,
+    pub period: Duration,
+}
+
+impl Default for Intensity {
+    fn default() -> Self {
+        Intensity {
+            max_restarts: 3,
+            period: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Delay before restarting the child. Each consecutive restart of the same child multiplies
+/// the delay until maximum is reached. Delay is reset if the child has been running for
+/// longer than [Intensity] period.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub multiplier: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            initial: Duration::from_secs(0),
+            max: Duration::from_secs(0),
+            multiplier: 1,
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before the restart given the delay computed after the previous one. Initial
+    /// delay is used for the first restart and if the child was running long enough.
+    fn delay(&self, previous: Option<Duration>, long_running: bool) -> Duration {
+        match previous {
+            Some(delay) if !long_running => delay,
+            _ => self.initial,
+        }
+    }
+
+    /// Delay of the restart that follows the one with given delay.
+    fn next(&self, delay: Duration) -> Duration {
+        let max = self.max.max(self.initial);
+        delay.checked_mul(self.multiplier).map_or(max, |d| d.min(max))
+    }
+}
+
+/// Restarts that happened within the last [Intensity] period.
+struct RestartLog {
+    restarts: VecDeque<Instant>,
+}
+
+impl RestartLog {
+    fn new() -> Self {
+        RestartLog {
+            restarts: VecDeque::new(),
+        }
+    }
+
+    /// Remember the restart. Returns false if intensity was exceeded.
+    fn record(&mut self, intensity: &Intensity, now: Instant) -> bool {
+        while let Some(first) = self.restarts.front() {
+            if now.duration_since(*first) > intensity.period {
+                self.restarts.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.restarts.push_back(now);
+        self.restarts.len() <= intensity.max_restarts as usize
+    }
+}
+
+/// Whether the child started at given instant has been running for at least the period.
+/// Child that was never started is considered to be running long enough.
+fn ran_for(started_at: Option<Instant>, now: Instant, period: Duration) -> bool {
+    started_at.is_none_or(|t| now.duration_since(t) >= period)
+}
+
+/// How often the state of the ceasing child is checked.
+const CEASE_POLL: Duration = Duration::from_millis(10);
+
+/// Specification of the supervised child.
+pub struct ChildSpec<'a, 'b> {
+    pub builder: ThreadBuilder<'a, 'b>,
+    pub restart: Restart,
+}
+
+struct Child<'a, 'b> {
+    spec: ChildSpec<'a, 'b>,
+    thread: Option<OwnedThread>,
+    started_at: Option<Instant>,
+    /// Delay of the next restart. None until the child is restarted for the first time.
+    delay: Option<Duration>,
+}
+
+/// Error that stopped the supervisor.
+pub enum SupervisorError {
+    /// Child with given index failed to be built.
+    Build {
+        child: usize,
+        error: ThreadBuildError,
+    },
+
+    /// Children were restarted too often. All children were requested to cease.
+    IntensityExceeded,
+}
+
+/// Supervisor of the set of threads.
+pub struct Supervisor<'a, 'b> {
+    strategy: Strategy,
+    intensity: Intensity,
+    backoff: Backoff,
+    shutdown_timeout: Duration,
+    children: Vec<Child<'a, 'b>>,
+    restarts: RestartLog,
+}
+
+impl<'a, 'b> Supervisor<'a, 'b> {
+    /// Create new supervisor with default intensity and no backoff.
+    pub fn new(strategy: Strategy) -> Self {
+        Supervisor {
+            strategy,
+            intensity: Default::default(),
+            backoff: Default::default(),
+            shutdown_timeout: Duration::from_secs(5),
+            children: Vec::new(),
+            restarts: RestartLog::new(),
+        }
+    }
+
+    /// Set maximum restart intensity.
+    pub fn with_intensity(mut self, intensity: Intensity) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Set delay policy for restarts.
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Set time given to the sibling to cease before it is killed when it has to be restarted.
+    /// Guarded siblings are waited for until they cease.
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Add child to the supervisor. Children are started in the order they were added.
+    pub fn with_child(mut self, spec: ChildSpec<'a, 'b>) -> Self {
+        self.children.push(Child {
+            spec,
+            thread: None,
+            started_at: None,
+            delay: None,
+        });
+        self
+    }
+
+    /// Strategy used by this supervisor.
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    /// Amount of children of this supervisor.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Whether supervisor has no children.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Thread of the child with given index. None is returned if child is not started.
+    pub fn child(&self, index: usize) -> Option<&OwnedThread> {
+        self.children.get(index).and_then(|c| c.thread.as_ref())
+    }
+
+    /// Start all children that are not yet started. If any child fails to start then
+    /// all children that were already started are requested to cease.
+    pub fn start(&mut self) -> Result<(), SupervisorError> {
+        for i in 0..self.children.len() {
+            if self.children[i].thread.is_some() {
+                continue;
+            }
+            if let Err(error) = self.start_child(i) {
+                self.shutdown();
+                return Err(SupervisorError::Build { child: i, error });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the state of all children and restart dead ones according to the strategy.
+    /// Siblings that have to be restarted together with the dead child are ceased and waited
+    /// for before being rebuilt. Each death counts as one restart towards the [Intensity]
+    /// no matter how many children are restarted. Returns the amount of restarted children.
+    pub fn check(&mut self) -> Result<usize, SupervisorError> {
+        for child in self.children.iter_mut() {
+            if let Some(thread) = child.thread.as_mut() {
+                thread.refresh();
+            }
+        }
+
+        let mut restarted = 0;
+        while let Some(dead) = (0..self.children.len()).find(|i| self.is_dead(*i)) {
+            if !self.should_restart(dead) {
+                self.children[dead].thread = None;
+                continue;
+            }
+            self.register_restart()?;
+
+            let range = match self.strategy {
+                Strategy::OneForOne => dead..dead + 1,
+                Strategy::OneForAll => 0..self.children.len(),
+                Strategy::RestForOne => dead..self.children.len(),
+            };
+
+            // Siblings are ceased in reverse order of their start.
+            for i in range.clone().rev() {
+                self.stop_child(i);
+            }
+            for i in range {
+                if i != dead && self.children[i].spec.restart == Restart::Temporary {
+                    self.children[i].thread = None;
+                } else if self.children[i].thread.is_some() {
+                    self.restart_child(i)?;
+                    restarted += 1;
+                }
+            }
+        }
+        Ok(restarted)
+    }
+
+    /// Request all children to cease in reverse order of their start.
+    pub fn shutdown(&mut self) {
+        for child in self.children.iter_mut().rev() {
+            if let Some(mut thread) = child.thread.take() {
+                if !thread.state().is_dead() {
+                    thread.request_cease();
+                }
+            }
+        }
+    }
+
+    fn is_dead(&self, i: usize) -> bool {
+        match &self.children[i].thread {
+            Some(thread) => thread.state().is_dead(),
+            None => false,
+        }
+    }
+
+    /// Whether the dead child should be restarted according to its restart policy.
+    fn should_restart(&self, i: usize) -> bool {
+        let child = &self.children[i];
+        match (child.spec.restart, &child.thread) {
+            (Restart::Permanent, _) => true,
+            (Restart::Transient, Some(thread)) => !thread.state().is_ceased(),
+            (Restart::Transient, None) => false,
+            (Restart::Temporary, _) => false,
+        }
+    }
+
+    fn start_child(&mut self, i: usize) -> Result<(), ThreadBuildError> {
+        let child = &mut self.children[i];
+        child.thread = Some(child.spec.builder.build()?);
+        child.started_at = Some(clock::monotonic());
+        Ok(())
+    }
+
+    /// Request the child to cease and wait until it dies. Child that does not cease within
+    /// the shutdown timeout is killed unless it is guarded.
+    fn stop_child(&mut self, i: usize) {
+        let timeout = self.shutdown_timeout;
+        let thread = match self.children[i].thread.as_mut() {
+            Some(thread) => thread,
+            None => return,
+        };
+        if thread.state().is_dead() {
+            return;
+        }
+        thread.request_cease();
+        let requested = clock::monotonic();
+        let mut kill_tried = false;
+        loop {
+            thread.refresh();
+            if thread.state().is_dead() {
+                return;
+            }
+            if !kill_tried && requested.elapsed() >= timeout {
+                kill_tried = true;
+                // Guarded thread keeps ceasing on its own.
+                let _ = unsafe { thread.brute_kill() };
+                continue;
+            }
+            OwnedThread::current().sleep(CEASE_POLL);
+        }
+    }
+
+    /// Rebuild the dead child after its backoff delay.
+    fn restart_child(&mut self, i: usize) -> Result<(), SupervisorError> {
+        let delay = self.take_delay(i, clock::monotonic());
+        if delay > Duration::from_secs(0) {
+            OwnedThread::current().sleep(delay);
+        }
+
+        let child = &mut self.children[i];
+        let result = match &child.thread {
+            Some(old) => child.spec.builder.rebuild(old),
+            None => child.spec.builder.build(),
+        };
+        match result {
+            Ok(thread) => {
+                child.thread = Some(thread);
+                child.started_at = Some(clock::monotonic());
+                Ok(())
+            },
+            Err(error) => {
+                self.shutdown();
+                Err(SupervisorError::Build { child: i, error })
+            },
+        }
+    }
+
+    /// Delay before restarting the child at given instant. Delay of the following restart is
+    /// remembered.
+    fn take_delay(&mut self, i: usize, now: Instant) -> Duration {
+        let long_running = ran_for(self.children[i].started_at, now, self.intensity.period);
+        let child = &mut self.children[i];
+        let delay = self.backoff.delay(child.delay, long_running);
+        child.delay = Some(self.backoff.next(delay));
+        delay
+    }
+
+    /// Remember the restart and check whether intensity was exceeded.
+    fn register_restart(&mut self) -> Result<(), SupervisorError> {
+        if self.restarts.record(&self.intensity, clock::monotonic()) {
+            Ok(())
+        } else {
+            self.shutdown();
+            Err(SupervisorError::IntensityExceeded)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use crate::path::{LocalPath, Version};
+    use crate::path::tests::interface;
+    use crate::thread::{Publicity, Type};
+
+    fn at(secs: u64) -> Instant {
+        Instant::from_start(Duration::from_secs(secs))
+    }
+
+    #[test]
+    fn intensity_window() {
+        let intensity = Intensity::default();
+        let mut log = RestartLog::new();
+        assert!(log.record(&intensity, at(0)));
+        assert!(log.record(&intensity, at(1)));
+        assert!(log.record(&intensity, at(2)));
+        assert!(!log.record(&intensity, at(3)));
+
+        let mut log = RestartLog::new();
+        assert!(log.record(&intensity, at(0)));
+        assert!(log.record(&intensity, at(1)));
+        assert!(log.record(&intensity, at(2)));
+        assert!(log.record(&intensity, at(6)));
+        assert!(log.record(&intensity, at(7)));
+        assert!(!log.record(&intensity, at(7)));
+    }
+
+    #[test]
+    fn backoff_delay() {
+        let backoff = Backoff {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(5),
+            multiplier: 2,
+        };
+        let mut delay = None;
+        let mut delays = Vec::new();
+        for _ in 0..5 {
+            let d = backoff.delay(delay, false);
+            delays.push(d.as_secs());
+            delay = Some(backoff.next(d));
+        }
+        assert_eq!(delays, [1, 2, 4, 5, 5]);
+        assert_eq!(backoff.delay(delay, true), backoff.initial);
+        assert_eq!(backoff.next(Duration::MAX), backoff.max);
+
+        let no_max = Backoff { max: Duration::from_secs(0), ..backoff };
+        assert_eq!(no_max.next(no_max.initial), no_max.initial);
+        assert_eq!(Backoff::default().next(Duration::from_secs(0)), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn backoff_set_after_children() {
+        let imp = interface("app", Version(1, 0, 0), vec![], vec![]);
+        let builder = ThreadBuilder::new(LocalPath::parse("worker").unwrap(), Type::Parallel,
+                                         Publicity::Public, &imp);
+        let mut supervisor = Supervisor::new(Strategy::OneForOne)
+            .with_child(ChildSpec { builder, restart: Restart::Permanent })
+            .with_backoff(Backoff {
+                initial: Duration::from_secs(1),
+                max: Duration::from_secs(10),
+                multiplier: 2,
+            });
+        supervisor.children[0].started_at = Some(at(0));
+        let delays: Vec<u64> = (1..4).map(|t| supervisor.take_delay(0, at(t)).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4]);
+    }
+
+    #[test]
+    fn long_running() {
+        let period = Duration::from_secs(5);
+        assert!(ran_for(None, at(0), period));
+        assert!(!ran_for(Some(at(1)), at(5), period));
+        assert!(ran_for(Some(at(1)), at(6), period));
+    }
+}
@@ -1,18 +1,73 @@
 use core::time::Duration;
 use time::Time;
-use crate::path::{LocalPath, Network, InstanceId, Interface};
+use crate::path::{LocalPath, Network, InstanceId, Interface, FindInstanceRequest, Path};
+use crate::version::VersionReq;
 use crate::{kobzar_env, KobzarEnv, Uid};
 use core::ops::Deref;
 use crate::rsc::{Variable, Handle};
 use alloc::rc::Rc;
+use alloc::vec::Vec;
+use alloc::boxed::Box;
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+use crate::watchdog::{Watchdog, WatchdogError};
 
 /// Priority identifies relative importance of the thread over other one. This helps scheduler to
 /// make correct decisions over which threads should be executed next and what time they should
 /// run.
-pub type Priority = f32;
+///
+/// Priority is in range from [Priority::MIN] to [Priority::MAX]. Task threads are compared
+/// only with the tasks of the same [Type] and their priority is the one in [TaskDetail].
+/// [Type::Parallel] threads are compared with other parallel threads. No thread can have
+/// priority above its [ceiling](Thread::priority_ceiling) which is set by its creator.
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+pub struct Priority(f32);
+
+impl Priority {
+    /// The least important priority.
+    pub const MIN: Priority = Priority(0.0);
+
+    /// Priority of threads that have no special requirements.
+    pub const DEFAULT: Priority = Priority(0.5);
+
+    /// The most important priority.
+    pub const MAX: Priority = Priority(1.0);
+
+    /// Create priority from the value in range from 0.0 to 1.0. None is returned if value is
+    /// out of the range.
+    pub fn new(value: f32) -> Option<Self> {
+        if (0.0..=1.0).contains(&value) {
+            Some(Priority(value))
+        } else {
+            None
+        }
+    }
+
+    /// Value of the priority in range from 0.0 to 1.0.
+    pub fn value(&self) -> f32 {
+        self.0
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::DEFAULT
+    }
+}
+
+/// Error returned when priority cannot be changed.
+pub enum PriorityError {
+    /// Requested priority is above the ceiling set for the thread.
+    AboveCeiling {
+        ceiling: Priority,
+    },
+
+    /// Caller has no rights to change priority of this thread.
+    NotPermitted,
+}
 
 /// State of the thread.
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum State {
     Running,
     Paused,
@@ -59,14 +114,106 @@ impl State {
     }
 }
 
-/// Performance policy defines the way CPU time is allocated for given thread.
+/// Performance policy defines the way CPU time is allocated for given thread. Policies are
+/// ordered from the most demanding to the least.
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 pub enum PerformancePolicy {
+    /// Thread is guaranteed to get the budget of CPU time in each period. Thread cannot
+    /// use more than the budget even if system is idle.
+    RealTime(RealTimeBudget),
+
     /// Request best performance possible for given thread.
     Performance,
 
     /// No special requests.
     Normal,
+
+    /// Thread prefers to save energy over finishing its work fast.
+    PowerSave,
+
+    /// Thread runs only when no other thread with higher policy is willing to run.
+    Background,
+}
+
+impl PerformancePolicy {
+    /// Create real-time policy. None is returned if budget is zero or exceeds the period.
+    pub fn real_time(budget: Duration, period: Duration) -> Option<Self> {
+        RealTimeBudget::new(budget, period).map(PerformancePolicy::RealTime)
+    }
+
+    /// Whether this is a real-time policy.
+    pub fn is_real_time(&self) -> bool {
+        matches!(self, PerformancePolicy::RealTime(_))
+    }
+}
+
+/// CPU time guaranteed to the real-time thread in each period. Budget is never zero and
+/// never exceeds the period.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub struct RealTimeBudget {
+    budget: Duration,
+    period: Duration,
+}
+
+impl RealTimeBudget {
+    /// Create real-time budget. None is returned if budget is zero or exceeds the period.
+    pub fn new(budget: Duration, period: Duration) -> Option<Self> {
+        if budget == Duration::from_secs(0) || budget > period {
+            None
+        } else {
+            Some(RealTimeBudget { budget, period })
+        }
+    }
+
+    /// CPU time guaranteed in each period.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Period in which the budget is renewed.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+}
+
+/// Power-saving mode changes the thread wants to be notified about. Notifications are
+/// delivered to the thread's mailbox as
+/// [PowerSaveNotification](crate::notif::PowerSaveNotification).
+#[derive(Clone, Copy, Eq, PartialEq, Default)]
+pub struct PowerSaveSubscription {
+    /// Notify when system enters power-saving mode.
+    pub enter: bool,
+
+    /// Notify when system leaves power-saving mode.
+    pub exit: bool,
+}
+
+/// Defines what happens to the children of a thread when the thread dies. Each thread that
+/// was built by another thread becomes its child and the network keeps track of this
+/// parent/child hierarchy.
+#[derive(Clone, Copy, Eq, PartialEq, Default)]
+pub enum OrphanPolicy {
+    /// Request all children to cease. Their own children are handled by their policies in turn.
+    #[default]
+    CascadeCease,
+
+    /// Kill all children immediately. Guarded children are requested to cease instead.
+    CascadeKill,
+
+    /// Pass all children to the given supervisor thread which becomes their new owner.
+    Reparent(Uid),
+}
+
+/// Error returned when orphan policy cannot be applied.
+pub enum OrphanPolicyError {
+    /// Supervisor thread with given UID was not found or is dead.
+    SupervisorNotFound,
+
+    /// Supervisor is a descendant of the thread and cannot adopt its ancestors' children.
+    SupervisorIsDescendant,
+
+    /// Thread has no rights to pass its children to given supervisor.
+    NotPermitted,
 }
 
 /// Detailed information about how and when to run this task.
@@ -76,7 +223,8 @@ pub struct TaskDetail {
     /// if task exceeded it's time during execution.
     pub estimate_left: Duration,
 
-    /// Margin for error for task execution time.
+    /// Margin for error for task execution time. When task exceeds both estimate and
+    /// margin it receives [EstimateOverrun](crate::notif::EstimateOverrun) notification.
     pub margin: Duration,
 
     /// Priority of this task over others of the same type.
@@ -103,7 +251,8 @@ pub enum Type {
 
         /// Time in the network until which the data should be cached.
         /// If scheduler fails to execute the task until this time the task will
-        /// be asked to provide new time, change task type or cease.
+        /// be asked to provide new time, change task type or cease by
+        /// [CacheExpiry](crate::notif::CacheExpiry) notification.
         until: Time,
     },
 
@@ -111,8 +260,78 @@ pub enum Type {
     Parallel,
 }
 
+impl Type {
+    /// Task details of the task types. None is returned for [Type::Parallel].
+    pub fn task_detail(&self) -> Option<&TaskDetail> {
+        match self {
+            Type::TimerTask(detail) => Some(detail),
+            Type::CachingTask { basic, .. } => Some(basic),
+            Type::Parallel => None,
+        }
+    }
+}
+
+/// Response of the task to the [EstimateOverrun](crate::notif::EstimateOverrun) notification.
+#[derive(Clone, PartialEq)]
+pub enum OverrunResponse {
+    /// Task needs given time more to finish.
+    Reestimate(Duration),
+
+    /// Task should be scheduled as given type from now on.
+    ChangeType(Type),
+
+    /// Task gives up and ceases.
+    GiveUp,
+}
+
+/// Response of the caching task to the [CacheExpiry](crate::notif::CacheExpiry) notification.
+#[derive(Clone, PartialEq)]
+pub enum ExpiryResponse {
+    /// Data should be cached until given time in the network.
+    ExtendUntil(Time),
+
+    /// Task should be scheduled as given type from now on.
+    ChangeType(Type),
+
+    /// Task ceases as cached data is no longer needed.
+    Cease,
+}
+
+/// Action taken by the scheduler if caching task does not respond to the
+/// [CacheExpiry](crate::notif::CacheExpiry) notification by the end of its next time slice.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExpiryPolicy {
+    /// Task is requested to cease.
+    #[default]
+    Cease,
+
+    /// Task `until` time is extended by given duration.
+    Extend(Duration),
+
+    /// Task continues as [Type::Parallel] thread.
+    Parallel,
+}
+
+/// Error returned when thread type cannot be changed.
+pub enum TypeChangeError {
+    /// Thread has no rights to use requested type.
+    NotPermitted,
+
+    /// Task details were given for the thread that is not a task.
+    NotATask,
+
+    /// Probability of the caching task is not in range from 0.0 to 1.0.
+    InvalidProbability,
+
+    /// Task priority is above the ceiling set for the thread.
+    PriorityAboveCeiling {
+        ceiling: Priority,
+    },
+}
+
 /// Thread that is owned by other thread. Owner can affect thread execution or change some
-/// the data associated with thread.
+/// the data associated with thread. Owned thread is a child of its owner in the thread tree
+/// and when owner dies the thread is handled according to owner's [OrphanPolicy].
 pub struct OwnedThread {
     thread: Thread,
 }
@@ -136,7 +355,8 @@ impl OwnedThread {
         kobzar_env().network_mut().request_pause(self)
     }
 
-    /// Notify thread to cease.
+    /// Notify thread to cease. When thread ceases its children are handled according to its
+    /// [OrphanPolicy].
     pub fn request_cease(&mut self) {
         kobzar_env().network_mut().request_cease(self)
     }
@@ -171,15 +391,177 @@ impl OwnedThread {
         }
     }
 
-    /// Get current performance policy.
-    pub fn performance_policy(&self) -> PerformancePolicy {
-        self.thread.performance
+    /// Tell the scheduler how to proceed after the task has exceeded its estimate and margin.
+    /// New type is checked the same way as in [set_type](Self::set_type). Err is returned if
+    /// task asks for the type it cannot use. In this case task continues with its current type.
+    pub fn respond_to_overrun(&mut self, response: OverrunResponse)
+                              -> Result<(), TypeChangeError> {
+        match &response {
+            OverrunResponse::Reestimate(_) if self.thread.ty.task_detail().is_none() => {
+                return Err(TypeChangeError::NotATask);
+            },
+            OverrunResponse::ChangeType(ty) => self.check_type(ty)?,
+            _ => (),
+        }
+        kobzar_env().network().respond_to_overrun(self, &response)?;
+        match response {
+            OverrunResponse::Reestimate(estimate) => {
+                if let Some(detail) = self.task_detail_mut() {
+                    detail.estimate_left = estimate;
+                }
+            },
+            OverrunResponse::ChangeType(ty) => self.store_type(ty),
+            OverrunResponse::GiveUp => (),
+        }
+        Ok(())
+    }
+
+    /// Tell the scheduler how to proceed after the caching task has missed its `until` time.
+    /// New type is checked the same way as in [set_type](Self::set_type). Err is returned if
+    /// task asks for the type it cannot use. In this case task [ExpiryPolicy] is applied.
+    pub fn respond_to_expiry(&mut self, response: ExpiryResponse)
+                             -> Result<(), TypeChangeError> {
+        if let ExpiryResponse::ChangeType(ty) = &response {
+            self.check_type(ty)?;
+        }
+        kobzar_env().network().respond_to_expiry(self, &response)?;
+        match response {
+            ExpiryResponse::ExtendUntil(time) => {
+                if let Type::CachingTask { until, .. } = &mut self.thread.ty {
+                    *until = time;
+                }
+            },
+            ExpiryResponse::ChangeType(ty) => self.store_type(ty),
+            ExpiryResponse::Cease => (),
+        }
+        Ok(())
+    }
+
+    /// Change the type of the thread. Owner should have rights to create threads of
+    /// the requested type.
+    pub fn set_type(&mut self, ty: Type) -> Result<(), TypeChangeError> {
+        self.check_type(&ty)?;
+        kobzar_env().network().set_type(self, &ty)?;
+        self.store_type(ty);
+        Ok(())
+    }
+
+    /// Check the type before asking the network to change it.
+    fn check_type(&self, ty: &Type) -> Result<(), TypeChangeError> {
+        if let Type::CachingTask { probability, .. } = ty {
+            if !(0.0..=1.0).contains(probability) {
+                return Err(TypeChangeError::InvalidProbability);
+            }
+        }
+        if let Some(detail) = ty.task_detail() {
+            if detail.priority > self.thread.priority_ceiling {
+                return Err(TypeChangeError::PriorityAboveCeiling {
+                    ceiling: self.thread.priority_ceiling,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn store_type(&mut self, ty: Type) {
+        if let Some(detail) = ty.task_detail() {
+            self.thread.priority = detail.priority;
+        }
+        self.thread.ty = ty;
+    }
+
+    fn task_detail_mut(&mut self) -> Option<&mut TaskDetail> {
+        match &mut self.thread.ty {
+            Type::TimerTask(detail) => Some(detail),
+            Type::CachingTask { basic, .. } => Some(basic),
+            Type::Parallel => None,
+        }
+    }
+
+    /// Change priority of the thread. For task threads the priority in [TaskDetail] is
+    /// changed.
+    pub fn set_priority(&mut self, priority: Priority) -> Result<(), PriorityError> {
+        if priority > self.thread.priority_ceiling {
+            return Err(PriorityError::AboveCeiling { ceiling: self.thread.priority_ceiling });
+        }
+        kobzar_env().network().set_priority(self, priority)?;
+        self.store_priority(priority);
+        Ok(())
+    }
+
+    /// Change the priority ceiling of the thread. Caller cannot set the ceiling above its
+    /// own one. If current priority is above the new ceiling it is lowered to the ceiling.
+    pub fn set_priority_ceiling(&mut self, ceiling: Priority) -> Result<(), PriorityError> {
+        kobzar_env().network().set_priority_ceiling(self, ceiling)?;
+        self.thread.priority_ceiling = ceiling;
+        if self.thread.priority > ceiling {
+            self.store_priority(ceiling);
+        }
+        Ok(())
+    }
+
+    fn store_priority(&mut self, priority: Priority) {
+        self.thread.priority = priority;
+        if let Some(detail) = self.task_detail_mut() {
+            detail.priority = priority;
+        }
+    }
+
+    /// Change task details of the task thread leaving its type unchanged. Can be used to
+    /// update estimate as the task makes progress.
+    pub fn set_task_detail(&mut self, detail: TaskDetail) -> Result<(), TypeChangeError> {
+        let ty = match &self.thread.ty {
+            Type::TimerTask(_) => Type::TimerTask(detail),
+            Type::CachingTask { probability, until, .. } => Type::CachingTask {
+                basic: detail,
+                probability: *probability,
+                until: *until,
+            },
+            Type::Parallel => return Err(TypeChangeError::NotATask),
+        };
+        self.set_type(ty)
+    }
+
+    /// Set or remove watchdog of the thread. Thread can set its own watchdog through
+    /// [OwnedThread::current]. Heartbeat period starts anew when watchdog is set.
+    pub fn set_watchdog(&mut self, watchdog: Option<Watchdog>) -> Result<(), WatchdogError> {
+        kobzar_env().network().set_watchdog(self, watchdog.as_ref())?;
+        self.thread.watchdog = watchdog;
+        Ok(())
+    }
+
+    /// Subscribe to or unsubscribe from power-saving mode notifications.
+    pub fn set_powersave_subscription(&mut self, subscription: PowerSaveSubscription) {
+        kobzar_env().network().set_powersave_subscription(self, subscription);
+        self.thread.has_powersave_notif = subscription.enter;
+        self.thread.has_powersave_disable_notif = subscription.exit;
+    }
+
+    /// Change the policy that is applied to the children of this thread when it dies.
+    pub fn set_orphan_policy(&mut self, policy: OrphanPolicy) -> Result<(), OrphanPolicyError> {
+        kobzar_env().network().set_orphan_policy(self, policy)?;
+        self.thread.orphan_policy = policy;
+        Ok(())
+    }
+
+    /// Download latest information about this thread.
+    pub fn refresh(&mut self) {
+        self.thread.update()
     }
 
     /// Get current thread handle.
     pub fn current() -> &'static mut OwnedThread {
         kobzar_env().network().current_thread()
     }
+
+    /// Startup arguments that were passed to the current thread by its creator with
+    /// [ThreadBuilder::with_args]. None is returned if thread was built without arguments.
+    pub fn startup_args<T: DeserializeOwned>() -> Result<Option<T>, postcard::Error> {
+        match kobzar_env().network().startup_args() {
+            Some(bytes) => postcard::from_bytes(bytes).map(Some),
+            None => Ok(None),
+        }
+    }
 }
 
 /// Publicity defines who can initiate communication with selected thread.
@@ -198,14 +580,38 @@ pub enum Publicity {
     Private,
 }
 
+impl Publicity {
+    /// Whether thread with the initiator path can initiate communication with the thread
+    /// with the target path. Package of the thread is the parent of its path.
+    pub fn allows(&self, target: &Path, initiator: &Path) -> bool {
+        let package = |p: &Path| p.parent().unwrap_or_else(|| p.clone());
+        let target = LocalPath::from(&package(target));
+        let initiator = package(initiator);
+        match self {
+            Publicity::Public => true,
+            Publicity::Package => initiator.starts_with(&target),
+            Publicity::Descendant => initiator.is_descendant_of(&target),
+            Publicity::Private => false,
+        }
+    }
+}
+
 /// General information about thread in the network.
 #[derive(Clone)]
 pub struct Thread {
     instance: Rc<InstanceId>,
     state: State,
+    ty: Type,
+    priority: Priority,
+    priority_ceiling: Priority,
     publicity: Publicity,
     performance: PerformancePolicy,
 
+    computing_unit: ComputingUnit,
+    parent: Option<Uid>,
+    orphan_policy: OrphanPolicy,
+    watchdog: Option<Watchdog>,
+
     has_powersave_notif: bool,
     has_powersave_disable_notif: bool,
 }
@@ -215,6 +621,75 @@ impl Thread {
     pub fn state(&self) -> State {
         self.state
     }
+
+    /// Type of the thread when the snapshot was taken.
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    /// Priority of the thread.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Priority above which thread cannot be raised.
+    pub fn priority_ceiling(&self) -> Priority {
+        self.priority_ceiling
+    }
+
+    /// Instance that this thread implements.
+    pub fn instance(&self) -> &Rc<InstanceId> {
+        &self.instance
+    }
+
+    /// Who can initiate communication with this thread.
+    pub fn publicity(&self) -> Publicity {
+        self.publicity
+    }
+
+    /// Current performance policy of the thread.
+    pub fn performance_policy(&self) -> PerformancePolicy {
+        self.performance
+    }
+
+    /// Power-saving mode notifications the thread is subscribed to.
+    pub fn powersave_subscription(&self) -> PowerSaveSubscription {
+        PowerSaveSubscription {
+            enter: self.has_powersave_notif,
+            exit: self.has_powersave_disable_notif,
+        }
+    }
+
+    /// Computing Unit the thread runs on.
+    pub fn computing_unit(&self) -> ComputingUnit {
+        self.computing_unit
+    }
+
+    /// UID of the thread that owns this one. None is returned for root threads.
+    pub fn parent(&self) -> Option<Uid> {
+        self.parent
+    }
+
+    /// Policy that is applied to the children of this thread when it dies.
+    pub fn orphan_policy(&self) -> OrphanPolicy {
+        self.orphan_policy
+    }
+
+    /// Resources used by this thread. Snapshot can be refreshed with
+    /// [ResourceUsage::refresh].
+    pub fn resource_usage(&self) -> ResourceUsage {
+        kobzar_env().network().resource_usage(self)
+    }
+
+    /// Watchdog of the thread.
+    pub fn watchdog(&self) -> Option<Watchdog> {
+        self.watchdog
+    }
+
+    /// Snapshots of all threads that are currently owned by this thread.
+    pub fn children(&self) -> Vec<Thread> {
+        kobzar_env().network().children(self)
+    }
 }
 
 impl Handle for Thread {
@@ -225,6 +700,110 @@ impl Handle for Thread {
 
 impl Variable for Thread {}
 
+/// Resources used by the thread when the snapshot was taken. Counters are accumulated since
+/// the thread was built.
+#[derive(Clone)]
+pub struct ResourceUsage {
+    thread: Uid,
+    cpu_time: Duration,
+    estimate_left: Option<Duration>,
+    overrun: Duration,
+    wakeups: u64,
+    messages_sent: u64,
+    messages_received: u64,
+    mailbox_depth: usize,
+    rendezvous_waits: u64,
+    memory: usize,
+}
+
+impl ResourceUsage {
+    /// UID of the thread this snapshot belongs to.
+    pub fn thread(&self) -> Uid {
+        self.thread
+    }
+
+    /// CPU time consumed by the thread.
+    pub fn cpu_time(&self) -> Duration {
+        self.cpu_time
+    }
+
+    /// Estimated time left according to the [TaskDetail] of the thread. None is returned for
+    /// threads that are not tasks.
+    pub fn estimate_left(&self) -> Option<Duration> {
+        self.estimate_left
+    }
+
+    /// CPU time the task has consumed over its estimate and margin. Zero if the task is
+    /// within its estimate or thread is not a task.
+    pub fn overrun(&self) -> Duration {
+        self.overrun
+    }
+
+    /// Whether the task has exceeded its estimate and margin.
+    pub fn is_overrun(&self) -> bool {
+        self.overrun > Duration::from_secs(0)
+    }
+
+    /// How many times the thread was woken up to run.
+    pub fn wakeups(&self) -> u64 {
+        self.wakeups
+    }
+
+    /// Amount of messages sent by the thread.
+    pub fn messages_sent(&self) -> u64 {
+        self.messages_sent
+    }
+
+    /// Amount of messages received by the thread.
+    pub fn messages_received(&self) -> u64 {
+        self.messages_received
+    }
+
+    /// Amount of unread messages in the mailbox.
+    pub fn mailbox_depth(&self) -> usize {
+        self.mailbox_depth
+    }
+
+    /// How many times the thread was blocked waiting for rendezvous.
+    pub fn rendezvous_waits(&self) -> u64 {
+        self.rendezvous_waits
+    }
+
+    /// Memory in bytes allocated by the thread.
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+
+    /// Download latest resource usage of the thread.
+    pub fn refresh(&mut self) {
+        self.update()
+    }
+}
+
+impl Handle for ResourceUsage {
+    fn uid(&self) -> Uid {
+        self.thread
+    }
+}
+
+impl Variable for ResourceUsage {}
+
+/// Computing Unit of the network. Threads on the same Computing Unit can
+/// [transfer time](crate::msg::Sender::transfer_time) to each other.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct ComputingUnit(pub Uid);
+
+/// State of the thread right after it is built.
+#[derive(Clone, Copy, Eq, PartialEq, Default)]
+pub enum InitialState {
+    /// Thread starts execution immediately.
+    #[default]
+    Running,
+
+    /// Thread waits until owner [allows](OwnedThread::allow_run) it to run.
+    Paused,
+}
+
 /// Information that is required to build a thread.
 pub struct ThreadBuilder<'a, 'b> {
     pub local_path: LocalPath<'a>,
@@ -232,6 +811,37 @@ pub struct ThreadBuilder<'a, 'b> {
     pub publicity: Publicity,
 
     pub imp: &'b Interface,
+
+    /// Stack size in bytes. None to use the default of the system.
+    pub stack_size: Option<usize>,
+
+    /// Maximum amount of memory in bytes the thread can allocate. None for no quota
+    /// except for the one of the creator.
+    pub memory_quota: Option<usize>,
+
+    /// Whether thread starts running right after it is built or waits paused.
+    pub initial_state: InitialState,
+
+    /// Computing Unit the thread should be run on. None to let the network decide.
+    pub affinity: Option<ComputingUnit>,
+
+    /// Performance policy the thread starts with.
+    pub performance: PerformancePolicy,
+
+    /// Startup arguments serialized by postcard. Thread reads them with
+    /// [OwnedThread::startup_args].
+    pub args: Option<Vec<u8>>,
+
+    /// Whether owner should also receive [EstimateOverrun](crate::notif::EstimateOverrun)
+    /// notifications of this task.
+    pub notify_owner_on_overrun: bool,
+
+    /// Action taken if caching task does not respond to the expiry notification.
+    pub expiry_policy: ExpiryPolicy,
+
+    /// Priority above which thread cannot be raised. None to use the ceiling of the creator.
+    /// Parallel threads start with [Priority::DEFAULT] limited by the ceiling.
+    pub priority_ceiling: Option<Priority>,
 }
 
 pub enum ThreadBuildError {
@@ -243,12 +853,242 @@ pub enum ThreadBuildError {
         most_supported: PerformancePolicy,
     },
 
+    /// Requested stack size is bigger than creator is allowed to request.
+    StackSizeNotPermitted {
+        max_supported: usize,
+    },
+
+    /// Requested memory quota is bigger than creator is allowed to grant.
+    MemoryQuotaNotPermitted {
+        max_supported: usize,
+    },
+
+    /// Creator has no rights to create paused threads.
+    InitialStateNotPermitted,
+
+    /// Requested priority or priority ceiling is above the ceiling of the creator.
+    PriorityNotPermitted {
+        max_supported: Priority,
+    },
+
+    /// Startup arguments are bigger than network can pass to the new thread.
+    ArgsTooLarge {
+        max_supported: usize,
+    },
+
+    /// Creator has no rights to run threads on requested Computing Unit.
+    AffinityNotPermitted,
+
+    /// Requested Computing Unit was not found in the network.
+    ComputingUnitNotFound,
+
     /// Implementation for requested interface was not found.
     NotFound,
+
+    /// Dependency of the interface failed to be instantiated.
+    DependencyFailed {
+        dependency: Rc<Interface>,
+        cause: Box<ThreadBuildError>,
+    },
+
+    /// Dependencies of the interface form a cycle. The dependency that closes the cycle
+    /// is given.
+    DependencyCycle {
+        dependency: Rc<Interface>,
+    },
+
+    /// Interface is a singleton and its implementer is already running.
+    SingletonRunning {
+        existing: Box<Thread>,
+    },
+}
+
+/// Result of [ThreadBuilder::build_or_existing].
+pub enum Built {
+    /// New thread was built.
+    New(OwnedThread),
+
+    /// Implementer of the singleton interface is already running.
+    Existing(Thread),
 }
 
 impl<'a, 'b> ThreadBuilder<'a, 'b> {
+    /// Create builder with default options. Thread will start running immediately with
+    /// normal performance policy on the Computing Unit chosen by the network.
+    pub fn new(local_path: LocalPath<'a>, ty: Type, publicity: Publicity, imp: &'b Interface)
+               -> Self {
+        ThreadBuilder {
+            local_path,
+            ty,
+            publicity,
+            imp,
+            stack_size: None,
+            memory_quota: None,
+            initial_state: InitialState::Running,
+            affinity: None,
+            performance: PerformancePolicy::Normal,
+            args: None,
+            notify_owner_on_overrun: false,
+            expiry_policy: ExpiryPolicy::Cease,
+            priority_ceiling: None,
+        }
+    }
+
+    /// Request stack of given size in bytes.
+    pub fn with_stack_size(mut self, size: usize) -> Self {
+        self.stack_size = Some(size);
+        self
+    }
+
+    /// Limit memory thread can allocate to given amount of bytes.
+    pub fn with_memory_quota(mut self, quota: usize) -> Self {
+        self.memory_quota = Some(quota);
+        self
+    }
+
+    /// Do not run thread until owner allows it.
+    pub fn paused(mut self) -> Self {
+        self.initial_state = InitialState::Paused;
+        self
+    }
+
+    /// Run thread on given Computing Unit.
+    pub fn with_affinity(mut self, unit: ComputingUnit) -> Self {
+        self.affinity = Some(unit);
+        self
+    }
+
+    /// Start thread with given performance policy.
+    pub fn with_performance_policy(mut self, policy: PerformancePolicy) -> Self {
+        self.performance = policy;
+        self
+    }
+
+    /// Notify owner when the task exceeds its estimate and margin.
+    pub fn notify_owner_on_overrun(mut self) -> Self {
+        self.notify_owner_on_overrun = true;
+        self
+    }
+
+    /// Set action taken if caching task does not respond to the expiry notification.
+    pub fn with_expiry_policy(mut self, policy: ExpiryPolicy) -> Self {
+        self.expiry_policy = policy;
+        self
+    }
+
+    /// Limit priority of the thread to given ceiling.
+    pub fn with_priority_ceiling(mut self, ceiling: Priority) -> Self {
+        self.priority_ceiling = Some(ceiling);
+        self
+    }
+
+    /// Pass startup arguments to the thread. Arguments are available to the thread from the
+    /// very start so there is no need to send them in a message after the thread is built.
+    pub fn with_args<T: Serialize>(mut self, args: &T) -> Result<Self, postcard::Error> {
+        self.args = Some(postcard::to_allocvec(args)?);
+        Ok(self)
+    }
+
+    /// Build the thread. All [dependencies](Interface::dependencies) of the implemented
+    /// interface are instantiated first in the order that each goes after its own
+    /// dependencies. Running instances of singleton dependencies are reused. Dependencies
+    /// that were started for this thread become its children.
+    ///
+    /// If implemented interface is a singleton and its implementer is already running
+    /// [SingletonRunning](ThreadBuildError::SingletonRunning) error is returned.
     pub fn build(&self) -> Result<OwnedThread, ThreadBuildError> {
-        kobzar_env().network_mut().create_thread(self)
+        self.build_with(None, || kobzar_env().network_mut().create_thread(self))
+    }
+
+    /// Build the thread or, if implemented interface is a singleton that is already running,
+    /// get the running instance.
+    pub fn build_or_existing(&self) -> Result<Built, ThreadBuildError> {
+        match self.build() {
+            Ok(thread) => Ok(Built::New(thread)),
+            Err(ThreadBuildError::SingletonRunning { existing }) => Ok(Built::Existing(*existing)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Build a thread that replaces the dead one. New thread takes over the instance of
+    /// the dead thread so that it is registered under the same [Interface] and can be
+    /// found by the clients that discovered the dead one. Dependencies are resolved
+    /// the same way as in [build](ThreadBuilder::build).
+    pub fn rebuild(&self, dead: &OwnedThread) -> Result<OwnedThread, ThreadBuildError> {
+        self.build_with(Some(dead.uid()),
+                        || kobzar_env().network_mut().recreate_thread(dead, self))
+    }
+
+    fn build_with<F>(&self, replaces: Option<Uid>, create: F)
+                     -> Result<OwnedThread, ThreadBuildError>
+        where F: FnOnce() -> Result<OwnedThread, ThreadBuildError> {
+        // Fast path to avoid starting dependencies needlessly. The network enforces the
+        // singleton atomically when the thread is created.
+        if let Some(existing) = kobzar_env().network().singleton_holder(self.imp) {
+            if Some(existing.uid()) != replaces {
+                return Err(ThreadBuildError::SingletonRunning {
+                    existing: Box::new(existing),
+                });
+            }
+        }
+
+        let order = self.imp.dependency_order()
+            .map_err(|dependency| ThreadBuildError::DependencyCycle { dependency })?;
+
+        let mut started = Vec::new();
+        for dep in order {
+            match self.instantiate_dependency(&dep) {
+                Ok(Some(t)) => started.push(t),
+                Ok(None) => (),
+                Err(cause) => {
+                    Self::cease_all(started);
+                    return Err(ThreadBuildError::DependencyFailed {
+                        dependency: dep.clone(),
+                        cause: Box::new(cause),
+                    });
+                },
+            }
+        }
+
+        match create() {
+            Ok(thread) => {
+                for dep in started {
+                    kobzar_env().network().adopt(&thread, dep);
+                }
+                Ok(thread)
+            },
+            Err(e) => {
+                Self::cease_all(started);
+                Err(e)
+            },
+        }
+    }
+
+    /// Start the dependency unless it is a singleton that is already running.
+    fn instantiate_dependency(&self, dep: &Interface)
+                              -> Result<Option<OwnedThread>, ThreadBuildError> {
+        if kobzar_env().network().singleton_holder(dep).is_some() {
+            return Ok(None);
+        }
+        if !dep.has_executable() {
+            let running = FindInstanceRequest::new(LocalPath::from(dep.path()))
+                .with_version(VersionReq::exact(dep.version()))
+                .find();
+            return if running.is_empty() {
+                Err(ThreadBuildError::NotFound)
+            } else {
+                Ok(None)
+            };
+        }
+
+        let builder = ThreadBuilder::new(LocalPath::from(dep.path()), Type::Parallel,
+                                         self.publicity, dep);
+        kobzar_env().network_mut().create_thread(&builder).map(Some)
+    }
+
+    fn cease_all(threads: Vec<OwnedThread>) {
+        for mut t in threads.into_iter().rev() {
+            t.request_cease();
+        }
     }
 }
@@ -0,0 +1,69 @@
+//! Timers send [TimerFired](crate::notif::TimerFired) message to the mailbox of the thread
+//! that started them. Unlike [OwnedThread::sleep](crate::thread::OwnedThread::sleep) they do
+//! not block the thread, so timeouts can be waited for together with other messages using
+//! [wait_any](crate::msg::wait_any).
+
+use core::time::Duration;
+use time::Time;
+use crate::path::Network;
+use crate::rsc::Handle;
+use crate::{kobzar_env, KobzarEnv, Uid};
+
+/// Moment when the timer fires for the first time.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Deadline {
+    /// Fire after given duration elapses.
+    After(Duration),
+
+    /// Fire at given time in the network. See [clock](crate::clock) to read network time.
+    At(Time),
+}
+
+/// Error returned when timer cannot be started.
+pub enum TimerError {
+    /// Period is shorter than network is able to serve.
+    PeriodTooShort {
+        min_supported: Duration,
+    },
+
+    /// Thread has reached the limit of active timers.
+    TooManyTimers,
+}
+
+/// Handle of the started timer. Dropping the handle does not cancel the timer.
+pub struct Timer {
+    uid: Uid,
+}
+
+impl Timer {
+    /// Start timer that fires once. Tag is sent back in
+    /// [TimerFired](crate::notif::TimerFired) to tell timers apart.
+    pub fn once(deadline: Deadline, tag: u64) -> Result<Timer, TimerError> {
+        let uid = kobzar_env().network().start_timer(&deadline, None, tag)?;
+        Ok(Timer { uid })
+    }
+
+    /// Start timer that fires at the deadline and then each period after it.
+    pub fn periodic(deadline: Deadline, period: Duration, tag: u64) -> Result<Timer, TimerError> {
+        let uid = kobzar_env().network().start_timer(&deadline, Some(period), tag)?;
+        Ok(Timer { uid })
+    }
+
+    /// UID of the timer. It is sent in [TimerFired](crate::notif::TimerFired) to tell timers
+    /// apart.
+    pub fn uid(&self) -> Uid {
+        self.uid
+    }
+
+    /// Cancel the timer. Message that was already sent stays in the mailbox. False is
+    /// returned if one-shot timer has already fired.
+    pub fn cancel(self) -> bool {
+        kobzar_env().network().cancel_timer(self.uid)
+    }
+}
+
+impl Handle for Timer {
+    fn uid(&self) -> Uid {
+        self.uid
+    }
+}
@@ -1,12 +1,21 @@
 #![allow(dead_code)]
 use crate::{KobzarEnv, PrivateKobzarEnv, Uid};
-use crate::path::{Network, FindInstanceRequest, InstanceId, Interface};
+use crate::path::{Network, FindInstanceRequest, InstanceId, Interface, InstancePage};
 use crate::rsc::{Variable};
-use smallvec::SmallVec;
-use crate::thread::{OwnedThread, ThreadBuildError, ThreadBuilder, PerformancePolicy};
-use alloc::sync::Arc;
+use crate::thread::{OwnedThread, ThreadBuildError, ThreadBuilder, PerformancePolicy, Thread,
+                    OrphanPolicy, OrphanPolicyError, PowerSaveSubscription, ResourceUsage,
+                    OverrunResponse, TypeChangeError, ExpiryResponse, Type, Priority,
+                    PriorityError};
+use alloc::vec::Vec;
+use alloc::boxed::Box;
 use core::time::Duration;
 use crate::msg::{Sender, Receiver, ReceiveError, Output, Input, SendError, MailboxSendError};
+use crate::notif::SystemInterface;
+use crate::watchdog::{Watchdog, WatchdogError};
+use crate::timer::{Deadline, TimerError};
+use crate::watch::WatchError;
+use crate::registry::RegistryError;
+use crate::clock::ClockBackend;
 use smallvec::alloc::rc::Rc;
 
 pub struct UnimplementedEnv;
@@ -39,8 +48,11 @@ pub(crate) fn kobzar_env() -> &'static mut UnimplementedEnv {
 }
 
 impl Network for UnimplementedNetwork {
-    fn find_package_instances(&self, _: &FindInstanceRequest)
-                              -> SmallVec<[Arc<InstanceId>; 16]> {
+    fn find_package_instances(&self, _: &FindInstanceRequest) -> InstancePage {
+        unimplemented!()
+    }
+
+    fn instance_thread(&self, _: &InstanceId) -> Option<Thread> {
         unimplemented!()
     }
 
@@ -48,6 +60,19 @@ impl Network for UnimplementedNetwork {
         unimplemented!()
     }
 
+    fn singleton_holder(&self, _: &Interface) -> Option<Thread> {
+        unimplemented!()
+    }
+
+    fn adopt(&self, _: &OwnedThread, _: OwnedThread) {
+        unimplemented!()
+    }
+
+    fn recreate_thread(&self, _: &OwnedThread, _: &ThreadBuilder)
+                       -> Result<OwnedThread, ThreadBuildError> {
+        unimplemented!()
+    }
+
     fn allow_run(&self, _: &OwnedThread) {
         unimplemented!()
     }
@@ -68,15 +93,101 @@ impl Network for UnimplementedNetwork {
         unimplemented!()
     }
 
+    fn clock(&self) -> &dyn ClockBackend {
+        unimplemented!()
+    }
+
+    fn set_clock_backend(&mut self, _: Box<dyn ClockBackend>) {
+        unimplemented!()
+    }
+
+    fn start_timer(&self, _: &Deadline, _: Option<Duration>, _: u64)
+                   -> Result<Uid, TimerError> {
+        unimplemented!()
+    }
+
+    fn cancel_timer(&self, _: Uid) -> bool {
+        unimplemented!()
+    }
+
+    fn start_watch(&self, _: &FindInstanceRequest) -> Result<(Uid, InstancePage), WatchError> {
+        unimplemented!()
+    }
+
+    fn cancel_watch(&self, _: Uid) -> bool {
+        unimplemented!()
+    }
+
+    fn register_interface(&self, _: Interface) -> Result<Rc<Interface>, RegistryError> {
+        unimplemented!()
+    }
+
+    fn publish_interface(&self, _: &Rc<Interface>) -> Result<Rc<InstanceId>, RegistryError> {
+        unimplemented!()
+    }
+
     fn set_performance_policy(&self, _: &OwnedThread, _: PerformancePolicy)
                               -> Result<(), PerformancePolicy> {
         unimplemented!()
     }
 
+    fn set_powersave_subscription(&self, _: &OwnedThread, _: PowerSaveSubscription) {
+        unimplemented!()
+    }
+
+    fn set_type(&self, _: &OwnedThread, _: &Type) -> Result<(), TypeChangeError> {
+        unimplemented!()
+    }
+
+    fn set_priority(&self, _: &OwnedThread, _: Priority) -> Result<(), PriorityError> {
+        unimplemented!()
+    }
+
+    fn set_priority_ceiling(&self, _: &OwnedThread, _: Priority)
+                            -> Result<(), PriorityError> {
+        unimplemented!()
+    }
+
+    fn respond_to_overrun(&self, _: &OwnedThread, _: &OverrunResponse)
+                          -> Result<(), TypeChangeError> {
+        unimplemented!()
+    }
+
+    fn respond_to_expiry(&self, _: &OwnedThread, _: &ExpiryResponse)
+                         -> Result<(), TypeChangeError> {
+        unimplemented!()
+    }
+
+    fn set_watchdog(&self, _: &OwnedThread, _: Option<&Watchdog>)
+                    -> Result<(), WatchdogError> {
+        unimplemented!()
+    }
+
+    fn heartbeat(&self) {
+        unimplemented!()
+    }
+
+    fn set_orphan_policy(&self, _: &OwnedThread, _: OrphanPolicy)
+                         -> Result<(), OrphanPolicyError> {
+        unimplemented!()
+    }
+
+    fn children(&self, _: &Thread) -> Vec<Thread> {
+        unimplemented!()
+    }
+
+    fn resource_usage(&self, _: &Thread) -> ResourceUsage {
+        unimplemented!()
+    }
+
     fn current_thread(&self) -> &'static mut OwnedThread {
         unimplemented!()
     }
 
+    fn startup_args(&self) -> Option<&'static [u8]> {
+        unimplemented!()
+    }
+
     fn send<O: Output>(&self, _: &Sender<O>, _: &O) -> Result<(), MailboxSendError> {
         unimplemented!()
     }
@@ -123,6 +234,10 @@ impl Network for UnimplementedNetwork {
         unimplemented!()
     }
 
+    fn system_interface(&self, _: SystemInterface) -> &'static Rc<Interface> {
+        unimplemented!()
+    }
+
     fn has_incoming(&self) -> bool {
         unimplemented!()
     }
@@ -0,0 +1,462 @@
+//! Version requirements. Requirement is written as a set of comparators separated by `,`
+//! which all must match. Several sets can be joined with `||` so that any of them can
+//! match. Comparators are:
+//!
+//! * `^1.2.3` or just `1.2.3` – compatible versions, `>=1.2.3, <2.0.0`;
+//! * `~1.2.3` – only patch updates, `>=1.2.3, <1.3.0`;
+//! * `=1.2.3` – exactly this version, `=1.2` or `1.2.*` – any `1.2.x` version;
+//! * `!=1.2.3` – any version except this one;
+//! * `>1.2.3`, `>=1.2.3`, `<1.2.3`, `<=1.2.3` – plain comparisons;
+//! * `*` – any version.
+//!
+//! Minor and patch parts may be omitted. For example, `^2, !=2.3.1` is any `2.x` version
+//! except `2.3.1` and `~1.4 || ^2` is any `1.4.x` or `2.x` version.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+use core::str::FromStr;
+use crate::path::Version;
+
+/// Error of parsing version or version requirement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionParseError {
+    /// String or one of its comparators is empty.
+    Empty,
+
+    /// Part of the version with given index is not a number.
+    InvalidNumber {
+        part: usize,
+    },
+
+    /// Version has more than three parts.
+    TooManyParts,
+
+    /// Version has less than three parts while full version is required.
+    MissingParts,
+
+    /// Part with given index follows the wildcard part.
+    PartAfterWildcard {
+        part: usize,
+    },
+}
+
+impl fmt::Display for VersionParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VersionParseError::Empty => write!(f, "version is empty"),
+            VersionParseError::InvalidNumber { part } =>
+                write!(f, "part {} of the version is not a number", part),
+            VersionParseError::TooManyParts => write!(f, "version has more than three parts"),
+            VersionParseError::MissingParts => write!(f, "version has less than three parts"),
+            VersionParseError::PartAfterWildcard { part } =>
+                write!(f, "part {} of the version follows the wildcard", part),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+impl FromStr for Version {
+    type Err = VersionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let partial = PartialVersion::parse(s)?;
+        match (partial.minor, partial.patch) {
+            (Some(minor), Some(patch)) => Ok(Version(partial.major, minor, patch)),
+            _ => Err(VersionParseError::MissingParts),
+        }
+    }
+}
+
+/// Version which minor and patch parts may be omitted.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct PartialVersion {
+    major: u32,
+    minor: Option<u32>,
+    patch: Option<u32>,
+}
+
+impl PartialVersion {
+    fn parse(s: &str) -> Result<Self, VersionParseError> {
+        if s.is_empty() {
+            return Err(VersionParseError::Empty);
+        }
+        let mut parts = [None; 3];
+        let mut wildcard = false;
+        for (i, part) in s.split('.').enumerate() {
+            if i >= parts.len() {
+                return Err(VersionParseError::TooManyParts);
+            }
+            if wildcard {
+                return Err(VersionParseError::PartAfterWildcard { part: i });
+            }
+            if i > 0 && (part == "*" || part == "x") {
+                wildcard = true;
+                continue;
+            }
+            let n = part.parse().map_err(|_| VersionParseError::InvalidNumber { part: i })?;
+            parts[i] = Some(n);
+        }
+        Ok(PartialVersion {
+            major: parts[0].ok_or(VersionParseError::InvalidNumber { part: 0 })?,
+            minor: parts[1],
+            patch: parts[2],
+        })
+    }
+
+    /// The lowest version that matches this partial version.
+    fn lowest(&self) -> Version {
+        Version(self.major, self.minor.unwrap_or(0), self.patch.unwrap_or(0))
+    }
+
+    /// The lowest version that is above all versions matching this partial version. None is
+    /// returned if it cannot be represented, that is, there is no upper bound.
+    fn above(&self) -> Option<Version> {
+        bump(self.major, self.minor, self.patch)
+    }
+
+    /// Whether version matches this partial version.
+    fn contains(&self, v: Version) -> bool {
+        within(v, self.lowest(), self.above())
+    }
+}
+
+/// Version with the last given part incremented and the following parts reset. Overflowing
+/// part carries into the previous one. None is returned only if major part overflows.
+fn bump(major: u32, minor: Option<u32>, patch: Option<u32>) -> Option<Version> {
+    match (minor, patch) {
+        (Some(minor), Some(patch)) => match patch.checked_add(1) {
+            Some(p) => Some(Version(major, minor, p)),
+            None => bump(major, Some(minor), None),
+        },
+        (Some(minor), None) => match minor.checked_add(1) {
+            Some(m) => Some(Version(major, m, 0)),
+            None => bump(major, None, None),
+        },
+        _ => major.checked_add(1).map(|m| Version(m, 0, 0)),
+    }
+}
+
+/// Whether version is at least `lower` and below `upper`. None `upper` means no upper bound.
+fn within(v: Version, lower: Version, upper: Option<Version>) -> bool {
+    v >= lower && upper.is_none_or(|u| v < u)
+}
+
+impl fmt::Display for PartialVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{}", minor)?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{}", patch)?;
+        }
+        Ok(())
+    }
+}
+
+/// Operation of the [Comparator].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Caret,
+    Tilde,
+    Exact,
+    Exclude,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+}
+
+/// Single condition of the version requirement.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Comparator {
+    op: Op,
+    version: PartialVersion,
+}
+
+impl Comparator {
+    /// Operation of the comparator.
+    pub fn op(&self) -> Op {
+        self.op
+    }
+
+    /// The lowest version that is compared. Omitted parts are zero.
+    pub fn version(&self) -> Version {
+        self.version.lowest()
+    }
+
+    /// Whether version satisfies the comparator.
+    pub fn matches(&self, v: Version) -> bool {
+        let p = &self.version;
+        match self.op {
+            Op::Caret => {
+                let upper = match (p.major, p.minor, p.patch) {
+                    (0, Some(0), Some(patch)) => bump(0, Some(0), Some(patch)),
+                    (0, Some(minor), _) => bump(0, Some(minor), None),
+                    (major, _, _) => bump(major, None, None),
+                };
+                within(v, p.lowest(), upper)
+            },
+            Op::Tilde => within(v, p.lowest(), bump(p.major, p.minor, None)),
+            Op::Exact => p.contains(v),
+            Op::Exclude => !p.contains(v),
+            Op::Greater => p.above().is_some_and(|a| v >= a),
+            Op::GreaterEq => v >= p.lowest(),
+            Op::Less => v < p.lowest(),
+            Op::LessEq => p.above().is_none_or(|a| v < a),
+        }
+    }
+
+    fn parse(s: &str) -> Result<Self, VersionParseError> {
+        const OPS: [(&str, Op); 8] = [
+            ("!=", Op::Exclude),
+            (">=", Op::GreaterEq),
+            ("<=", Op::LessEq),
+            (">", Op::Greater),
+            ("<", Op::Less),
+            ("=", Op::Exact),
+            ("^", Op::Caret),
+            ("~", Op::Tilde),
+        ];
+        let s = s.trim();
+        let (op, rest) = OPS.iter()
+            .find(|(prefix, _)| s.starts_with(prefix))
+            .map(|(prefix, op)| (*op, &s[prefix.len()..]))
+            .unwrap_or((Op::Caret, s));
+        let version = PartialVersion::parse(rest.trim())?;
+
+        // Wildcard means any version of given precision.
+        let op = if op == Op::Caret && rest.ends_with(['*', 'x']) { Op::Exact } else { op };
+        Ok(Comparator { op, version })
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let op = match self.op {
+            Op::Caret => "^",
+            Op::Tilde => "~",
+            Op::Exact => "=",
+            Op::Exclude => "!=",
+            Op::Greater => ">",
+            Op::GreaterEq => ">=",
+            Op::Less => "<",
+            Op::LessEq => "<=",
+        };
+        write!(f, "{}{}", op, self.version)
+    }
+}
+
+/// Requirement that versions should satisfy. See [module](self) documentation for
+/// the syntax.
+#[derive(Clone, PartialEq, Eq)]
+pub struct VersionReq {
+    alternatives: Vec<Vec<Comparator>>,
+}
+
+impl VersionReq {
+    /// Requirement that any version satisfies.
+    pub fn any() -> Self {
+        VersionReq {
+            alternatives: alloc::vec![Vec::new()],
+        }
+    }
+
+    /// Requirement that only given version satisfies.
+    pub fn exact(v: Version) -> Self {
+        Self::single(Op::Exact, v)
+    }
+
+    /// Requirement that versions compatible with given one satisfy, like `^1.2.3`.
+    pub fn compatible(v: Version) -> Self {
+        Self::single(Op::Caret, v)
+    }
+
+    fn single(op: Op, v: Version) -> Self {
+        let version = PartialVersion {
+            major: v.0,
+            minor: Some(v.1),
+            patch: Some(v.2),
+        };
+        VersionReq {
+            alternatives: alloc::vec![alloc::vec![Comparator { op, version }]],
+        }
+    }
+
+    /// Parse the requirement from the string.
+    pub fn parse(s: &str) -> Result<Self, VersionParseError> {
+        let mut alternatives = Vec::new();
+        for set in s.split("||") {
+            let set = set.trim();
+            if set == "*" {
+                alternatives.push(Vec::new());
+                continue;
+            }
+            let comparators = set.split(',')
+                .map(Comparator::parse)
+                .collect::<Result<Vec<_>, _>>()?;
+            alternatives.push(comparators);
+        }
+        Ok(VersionReq { alternatives })
+    }
+
+    /// Sets of comparators. Version satisfies requirement if it matches all comparators of
+    /// any set.
+    pub fn alternatives(&self) -> &[Vec<Comparator>] {
+        &self.alternatives
+    }
+
+    /// Whether version satisfies the requirement.
+    pub fn matches(&self, v: Version) -> bool {
+        self.alternatives.iter().any(|set| set.iter().all(|c| c.matches(v)))
+    }
+}
+
+impl From<Range<Version>> for VersionReq {
+    fn from(range: Range<Version>) -> Self {
+        let bound = |op, v: Version| Comparator {
+            op,
+            version: PartialVersion {
+                major: v.0,
+                minor: Some(v.1),
+                patch: Some(v.2),
+            },
+        };
+        VersionReq {
+            alternatives: alloc::vec![alloc::vec![
+                bound(Op::GreaterEq, range.start),
+                bound(Op::Less, range.end),
+            ]],
+        }
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = VersionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        VersionReq::parse(s)
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, set) in self.alternatives.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" || ")?;
+            }
+            if set.is_empty() {
+                f.write_str("*")?;
+            }
+            for (j, c) in set.iter().enumerate() {
+                if j > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn matches(req: &str, v: &str) -> bool {
+        req.parse::<VersionReq>().unwrap().matches(v.parse().unwrap())
+    }
+
+    #[test]
+    fn parse_and_display_version() {
+        let v: Version = "1.20.3".parse().unwrap();
+        assert!(v == Version(1, 20, 3));
+        assert_eq!(v.to_string(), "1.20.3");
+        assert_eq!("1.2".parse::<Version>().err(), Some(VersionParseError::MissingParts));
+        assert_eq!("1.2.3.4".parse::<Version>().err(), Some(VersionParseError::TooManyParts));
+        assert_eq!("1.a.3".parse::<Version>().err(),
+                   Some(VersionParseError::InvalidNumber { part: 1 }));
+    }
+
+    #[test]
+    fn overflowing_bounds() {
+        let max = u32::MAX;
+        assert!(matches(&alloc::format!("=1.2.{}", max), &alloc::format!("1.2.{}", max)));
+        assert!(matches(&alloc::format!("<=1.{}", max), "1.5.0"));
+        assert!(!matches(&alloc::format!(">{}", max), &alloc::format!("{}.1.0", max)));
+        assert!(matches(&alloc::format!("^{}.1", max), &alloc::format!("{}.9.0", max)));
+        assert!(matches(&alloc::format!("~1.{}", max), &alloc::format!("1.{}.7", max)));
+        assert!(!matches(&alloc::format!("~1.{}", max), &alloc::format!("1.{}.7", max - 1)));
+
+        let exact = alloc::format!("=1.2.{}", max);
+        assert!(!matches(&exact, "1.3.0"));
+        assert!(!matches(&exact, "5.0.0"));
+        assert!(!matches(&alloc::format!("<=1.2.{}", max), "9.0.0"));
+        assert!(!matches(&alloc::format!("~1.{}", max), "2.0.0"));
+        assert!(!matches(&alloc::format!("^0.{}", max), "1.0.0"));
+        assert!(matches(&alloc::format!("!=1.2.{}", max), "3.0.0"));
+        assert!(matches(&alloc::format!(">1.2.{}", max), "1.3.0"));
+        let highest = alloc::format!("{}.{}.{}", max, max, max);
+        assert!(matches(&alloc::format!(">={}.0.0", max), &highest));
+    }
+
+    #[test]
+    fn caret_and_tilde() {
+        assert!(matches("^1.2", "1.9.0"));
+        assert!(!matches("^1.2", "2.0.0"));
+        assert!(!matches("^1.2", "1.1.9"));
+        assert!(matches("^0.2.3", "0.2.9"));
+        assert!(!matches("^0.2.3", "0.3.0"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches("1.2.3", "1.4.0"));
+        assert!(matches("~1.2.3", "1.2.9"));
+        assert!(!matches("~1.2.3", "1.3.0"));
+        assert!(matches("~1", "1.9.9"));
+    }
+
+    #[test]
+    fn exact_exclusion_and_alternatives() {
+        assert!(matches("=1.2.3", "1.2.3"));
+        assert!(!matches("=1.2.3", "1.2.4"));
+        assert!(matches("1.2.*", "1.2.7"));
+        assert!(matches("^2, !=2.3.1", "2.3.0"));
+        assert!(!matches("^2, !=2.3.1", "2.3.1"));
+        assert!(matches("~1.4 || ^3", "3.1.0"));
+        assert!(!matches("~1.4 || ^3", "2.0.0"));
+        assert!(matches("*", "7.0.0"));
+        assert!(matches(">1.2, <=2.0", "1.3.0"));
+        assert!(!matches(">1.2, <=2.0", "1.2.5"));
+        assert!(matches(">1.2, <=2.0", "2.0.9"));
+    }
+
+    #[test]
+    fn part_after_wildcard() {
+        assert_eq!("1.*.3".parse::<VersionReq>().err(),
+                   Some(VersionParseError::PartAfterWildcard { part: 2 }));
+        assert_eq!("1.x.x".parse::<VersionReq>().err(),
+                   Some(VersionParseError::PartAfterWildcard { part: 2 }));
+        assert!(matches("1.*", "1.9.0"));
+        assert!(!matches("1.*", "2.0.0"));
+    }
+
+    #[test]
+    fn requirement_round_trip() {
+        let req: VersionReq = "2.x, != 2.3.1 || ~1.4".parse().unwrap();
+        assert_eq!(req.to_string(), "=2, !=2.3.1 || ~1.4");
+        assert!(req.to_string().parse::<VersionReq>().unwrap() == req);
+    }
+
+    #[test]
+    fn range_requirement() {
+        let req = VersionReq::from(Version(1, 0, 0)..Version(1, 5, 0));
+        assert!(req.matches(Version(1, 4, 9)));
+        assert!(!req.matches(Version(1, 5, 0)));
+    }
+}
@@ -0,0 +1,57 @@
+//! Watches track instances that satisfy a [FindInstanceRequest] and send
+//! [InstanceEvent](crate::notif::InstanceEvent) messages to the mailbox of the thread that
+//! started them whenever matching instances appear, disappear or change state. Unlike
+//! [FindInstanceRequest::find] they do not require polling to notice a restarted service.
+
+use alloc::sync::Arc;
+use crate::path::{FindInstanceRequest, InstanceId, InstancePage, Network};
+use crate::rsc::Handle;
+use crate::{kobzar_env, KobzarEnv, Uid};
+
+/// Error returned when watch cannot be started.
+pub enum WatchError {
+    /// Thread has no rights to watch instances matching the request.
+    NotPermitted,
+
+    /// Thread has reached the limit of active watches.
+    TooManyWatches,
+}
+
+/// Handle of the started watch. Dropping the handle does not cancel the watch.
+pub struct Watch {
+    uid: Uid,
+    snapshot: InstancePage,
+}
+
+impl Watch {
+    /// Start watching instances that satisfy the request. Offset and limit of the request
+    /// are ignored. Events that happen after the snapshot is taken are sent to the mailbox
+    /// so no changes are missed.
+    pub fn start(find: &FindInstanceRequest) -> Result<Watch, WatchError> {
+        let (uid, snapshot) = kobzar_env().network().start_watch(find)?;
+        Ok(Watch { uid, snapshot })
+    }
+
+    /// Instances that matched the request when the watch was started.
+    pub fn snapshot(&self) -> &[Arc<InstanceId>] {
+        self.snapshot.instances()
+    }
+
+    /// UID of the watch. It is sent in [InstanceEvent](crate::notif::InstanceEvent) to tell
+    /// watches apart.
+    pub fn uid(&self) -> Uid {
+        self.uid
+    }
+
+    /// Stop the watch. Events that were already sent stay in the mailbox. False is returned
+    /// if the watch is no longer active.
+    pub fn cancel(self) -> bool {
+        kobzar_env().network().cancel_watch(self.uid)
+    }
+}
+
+impl Handle for Watch {
+    fn uid(&self) -> Uid {
+        self.uid
+    }
+}
@@ -0,0 +1,49 @@
+//! Watchdog detects threads that are running but hung. Thread with a watchdog should call
+//! [heartbeat] at least once per its interval. Network checks the heartbeats and when
+//! one is missed takes the configured [Action].
+
+use core::time::Duration;
+use crate::path::Network;
+use crate::{kobzar_env, KobzarEnv};
+use serde::{Serialize, Deserialize};
+
+/// Action taken by the network when heartbeat is missed. The owner always receives
+/// [HeartbeatMissed](crate::notif::HeartbeatMissed) notification.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// Only notify the owner.
+    NotifyOwner,
+
+    /// Request thread to cease.
+    RequestCease,
+
+    /// Kill the thread. Guarded thread is requested to cease instead.
+    BruteKill,
+}
+
+/// Watchdog configuration of the thread.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Watchdog {
+    /// Maximum time between two heartbeats.
+    pub interval: Duration,
+
+    /// Action taken when heartbeat is missed.
+    pub action: Action,
+}
+
+/// Error returned when watchdog cannot be set.
+pub enum WatchdogError {
+    /// Caller has no rights to change watchdog of the thread.
+    NotPermitted,
+
+    /// Interval is shorter than network is able to check.
+    IntervalTooShort {
+        min_supported: Duration,
+    },
+}
+
+/// Notify the watchdog of the current thread that the thread is alive. Does nothing if
+/// current thread has no watchdog.
+pub fn heartbeat() {
+    kobzar_env().network().heartbeat()
+}